@@ -1,21 +1,50 @@
-use std::rc::Rc;
+use std::{
+    io::stdout,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
+use futures::future::join_all;
 use ratatui::{
-    DefaultTerminal, Frame,
-    crossterm::event::KeyEvent,
-    layout::{Constraint, Layout, Rect},
+    DefaultTerminal, Frame, Terminal,
+    backend::TestBackend,
+    buffer::Buffer,
+    crossterm::{
+        event::{
+            DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEvent, KeyModifiers, MouseButton,
+            MouseEventKind,
+        },
+        execute,
+        style::Print,
+        terminal::{EnterAlternateScreen, SetTitle, enable_raw_mode},
+    },
+    layout::{Constraint, Layout, Position, Rect},
+    text::{Line, Span},
 };
 use strum::Display;
 use tracing::*;
 
 use crate::{
-    config::{self, Config},
+    clock::Clock,
+    command::{self, Command, LeagueAction, RegionAction, SpoilTarget},
+    config::{Config, DateRange, LeagueSort, TeamOverride, strings::Strings},
     event::{AppEvent, Event, EventHandler},
+    export::{self, Spoilers},
+    hooks, ipc,
+    models::{self, MatchState, ScheduleChanges},
+    notify::{self, Notifier},
     resources::ResourceManager,
     widgets::{
+        command_line::CommandLine,
+        details::{Details, GameDetails, format_length},
         events::{Events, ScheduleState},
         fillchar::FillChar,
+        health::Health,
         leagues::{Leagues, LeaguesState},
+        palette::Palette,
+        queue::Queue,
+        toast::{TOAST_DURATION, ToastLevel, Toasts},
     },
 };
 
@@ -23,7 +52,7 @@ use crate::{
 pub struct App {
     pub running: bool,
     pub events: EventHandler,
-    pub config: Rc<Config>,
+    pub config: Arc<Config>,
     pub resources: ResourceManager,
 
     pub mode: Mode,
@@ -31,9 +60,33 @@ pub struct App {
     pub leagues_state: LeaguesState,
     pub schedule: Events,
     pub schedule_state: ScheduleState,
+    pub toasts: Toasts,
+    notifier: Notifier,
+    pub command_line: Option<CommandLine>,
+    pub palette: Option<Palette>,
+    pub details: Option<Details>,
+    pub queue: Option<Queue>,
+    pub health: Option<Health>,
+    pub mouse_capture: bool,
+    last_refresh: Instant,
+    title: Option<String>,
+    // Shown above the schedule when `summary` is on
+    summary: String,
+    // Where each match of the `live_banner` was drawn, so clicking it selects the match
+    live_banner: Vec<(Rect, String)>,
+    // Selected league ids before each change, newest last
+    league_history: Vec<Vec<String>>,
+    last_live_refresh: Instant,
+    // Letters typed into the leagues pane with `type_ahead`, and when the last one was typed
+    type_ahead: (String, Instant),
+    // Shared with the schedule and the notifier
+    clock: Clock,
+    // The last error toast, so commands from the command socket can answer with it
+    last_error: Option<String>,
+    redraw: bool,
 }
 
-#[derive(Debug, Default, Display)]
+#[derive(Debug, Default, Display, Clone, Copy, PartialEq, Eq)]
 pub enum Mode {
     None,
     #[default]
@@ -41,103 +94,249 @@ pub enum Mode {
     Events,
 }
 
+// Panes in the order focus cycles through them
+const FOCUS_ORDER: &[Mode] = &[Mode::Leagues, Mode::Events];
+
 impl App {
-    pub fn new() -> Result<Self, config::Error> {
-        let config = Rc::new(Config::new()?);
-        let resources = ResourceManager::new(config.data_dir.clone());
+    pub fn new(config: Config) -> Self {
+        let config = Arc::new(config);
+        let events = EventHandler::new(config.tick_rate);
+        let resources = ResourceManager::from_config(&config, events.get_sender_clone());
         let schedule = Events::new(config.clone());
         let leagues = Leagues::new(config.clone());
-        let events = EventHandler::new();
+        let toasts = Toasts::new(config.clone());
+        let notifier = Notifier::new(config.clone());
+        let mut schedule_state = ScheduleState::default();
+        schedule_state.spoil_results = config.spoil_results;
+        schedule_state.spoil_matches = config.spoil_matches;
 
-        Ok(App {
+        App {
             running: true,
-            events: events,
-            config: config,
-            resources: resources,
+            events,
+            config,
+            resources,
             mode: Mode::default(),
-            leagues: leagues,
+            leagues,
             leagues_state: LeaguesState::default(),
-            schedule: schedule,
-            schedule_state: ScheduleState::default(),
-        })
+            schedule,
+            schedule_state,
+            toasts,
+            notifier,
+            command_line: None,
+            palette: None,
+            details: None,
+            queue: None,
+            health: None,
+            mouse_capture: false,
+            last_refresh: Instant::now(),
+            title: None,
+            summary: String::new(),
+            live_banner: Vec::new(),
+            league_history: Vec::new(),
+            last_live_refresh: Instant::now(),
+            type_ahead: (String::new(), Instant::now()),
+            clock: Clock::default(),
+            last_error: None,
+            redraw: true,
+        }
+    }
+
+    // Runs the app, the schedule, the notifier and the resources at the time of `clock`
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self.schedule.set_clock(clock);
+        self.notifier.set_clock(clock);
+        self.resources = self.resources.with_clock(clock);
+        self
     }
 
     pub fn init(&mut self) {
         self.events.send(AppEvent::ReloadLeagues);
-        self.schedule_state.spoil_results = self.config.spoil_results;
-        self.schedule_state.spoil_matches = self.config.spoil_matches;
+        if let Some(path) = self.config.socket_path()
+            && let Err(e) = ipc::listen(&path, self.events.get_sender_clone())
+        {
+            error!("Failed to listen on '{}': {:?}", path.display(), e);
+            self.toast(
+                ToastLevel::Error,
+                format!("Failed to open command socket: {}", e),
+            );
+        }
+        if self.config.mouse {
+            self.set_mouse_capture(true);
+        }
+        if self.config.offline {
+            self.toast(
+                ToastLevel::Info,
+                "Offline mode, only using cached data".to_string(),
+            );
+        }
+        if self.config.danger_accept_invalid_certs {
+            self.toast(
+                ToastLevel::Warning,
+                "TLS certificates are not verified, danger_accept_invalid_certs is on".to_string(),
+            );
+        }
+        for warning in self.config.warnings.clone() {
+            self.toast(ToastLevel::Warning, warning);
+        }
+    }
+
+    // Loads the leagues and the schedules of `default_leagues` without the event loop, so what
+    // `render` draws only depends on the data and the clock. With `Source::Replay` fixtures and
+    // a frozen `Clock` the frames are the same on every run
+    pub async fn load(&mut self) {
+        let Some(leagues) = self.resources.get_leagues().await else {
+            return;
+        };
+        self.leagues.set_leagues(leagues);
+        self.leagues
+            .select_first(&mut self.leagues_state.list_state);
+        for name in self.config.default_leagues.clone() {
+            let Some(id) = self.leagues.select_name(&name) else {
+                continue;
+            };
+            self.schedule.set_active(id.clone());
+            if let Some(mut events) = self.resources.fetch_schedule(&id).await {
+                TeamOverride::apply(&self.config.team_overrides, &mut events);
+                self.leagues.update_upcoming(&id, &events, self.clock.now());
+                self.schedule.add_events(id, events);
+            }
+        }
+        self.schedule_state.reselect_or_today(&self.schedule);
+        self.update_summary();
+    }
+
+    // Draws a frame into a buffer instead of the terminal
+    pub fn render(&mut self, width: u16, height: u16) -> std::io::Result<Buffer> {
+        let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+        terminal.draw(|frame| self.draw(frame, frame.area()))?;
+        Ok(terminal.backend().buffer().clone())
     }
 
     fn handle_up(&mut self) {
+        let selected = self.selection();
         match self.mode {
             Mode::None => {}
-            Mode::Leagues => self.leagues_state.list_state.scroll_up_by(1),
-            Mode::Events => self.schedule_state.scroll_up_by(1),
+            Mode::Leagues => self
+                .leagues
+                .select_previous(&mut self.leagues_state.list_state),
+            Mode::Events => self.schedule_state.scroll_up_by(&self.schedule, 1),
         }
+        self.redraw |= self.selection() != selected;
     }
 
     fn handle_down(&mut self) {
+        let selected = self.selection();
         match self.mode {
             Mode::None => {}
-            Mode::Leagues => self.leagues_state.list_state.scroll_down_by(1),
-            Mode::Events => self.schedule_state.scroll_down_by(1),
+            Mode::Leagues => self.leagues.select_next(&mut self.leagues_state.list_state),
+            Mode::Events => self.schedule_state.scroll_down_by(&self.schedule, 1),
         }
+        self.redraw |= self.selection() != selected;
+    }
+
+    // Scrolling at either end of a list changes nothing
+    fn selection(&self) -> (Option<usize>, Option<usize>) {
+        (
+            self.leagues_state.list_state.selected(),
+            self.schedule_state.selected,
+        )
     }
 
     fn handle_left(&mut self) {
-        match self.mode {
-            Mode::Leagues => {}
-            Mode::Events | Mode::None => {
-                self.mode = Mode::Leagues;
-                self.schedule_state.focused = false;
-                self.leagues_state.focused = true;
-            }
-        }
+        self.focus(Mode::Leagues);
     }
 
     fn handle_right(&mut self) {
-        match self.mode {
-            Mode::Leagues | Mode::None => {
-                self.mode = Mode::Events;
-                self.schedule_state.focused = true;
-                self.leagues_state.focused = false;
-            }
-            Mode::Events => {}
-        }
+        self.focus(Mode::Events);
+    }
+
+    fn focus(&mut self, mode: Mode) {
+        self.redraw |= self.mode != mode;
+        self.mode = mode;
+        self.leagues_state.focused = mode == Mode::Leagues;
+        self.schedule_state.focused = mode == Mode::Events;
+    }
+
+    // Moves focus `step` panes along `FOCUS_ORDER`, wrapping around
+    fn cycle_focus(&mut self, step: isize) {
+        let len = FOCUS_ORDER.len() as isize;
+        let next = match FOCUS_ORDER.iter().position(|m| *m == self.mode) {
+            Some(i) => (i as isize + step).rem_euclid(len),
+            None => 0,
+        };
+        self.focus(FOCUS_ORDER[next as usize]);
     }
 
     fn handle_select(&mut self) {
         match self.mode {
             Mode::None => {}
             Mode::Leagues => {
+                self.save_league_selection();
                 let id = self.leagues.select(&self.leagues_state.list_state);
                 if let Some((selected, id)) = id {
                     match selected {
-                        true => self.set_active(id),
+                        true => {
+                            self.leagues
+                                .add_recent(&id, &mut self.leagues_state.list_state);
+                            self.set_active(vec![id])
+                        }
                         false => self.schedule.unset_active(&id),
                     }
-                    self.schedule_state.select_today(&self.schedule);
+                    self.schedule_state.reselect_or_today(&self.schedule);
                 }
             }
             Mode::Events => {}
         }
     }
 
+    fn save_league_selection(&mut self) {
+        const MAX_HISTORY: usize = 50;
+        let ids = self.leagues.get_selected_ids();
+        if self.league_history.last() != Some(&ids) {
+            self.league_history.push(ids);
+        }
+        if self.league_history.len() > MAX_HISTORY {
+            self.league_history.remove(0);
+        }
+    }
+
+    fn undo_league_selection(&mut self) {
+        // Skip snapshots equal to the current selection, left by failed changes
+        let current = self.leagues.get_selected_ids();
+        while self.league_history.last() == Some(&current) {
+            self.league_history.pop();
+        }
+        let Some(ids) = self.league_history.pop() else {
+            self.toast(ToastLevel::Info, "Nothing to undo".to_string());
+            return;
+        };
+        self.set_selected_ids(&ids);
+        self.schedule_state.reselect_or_today(&self.schedule);
+    }
+
     fn reload_leagues(&mut self) {
         let sender = self.events.get_sender_clone();
         let resources = self.resources.clone();
         tokio::spawn(async move {
-            match resources.get_leagues().await {
-                Some(leagues) => sender
+            if let Some(leagues) = resources.get_leagues().await {
+                let ids: Vec<String> = leagues.iter().map(|l| l.id.to_string()).collect();
+                sender
                     .send(Event::App(AppEvent::RecieveLeagues(leagues)))
-                    .unwrap(),
-                None => {}
+                    .unwrap();
+                let upcoming = resources.leagues_with_upcoming(&ids).await;
+                let _ = sender.send(Event::App(AppEvent::RecieveUpcoming(upcoming)));
             };
         });
     }
 
-    fn reload_schedule(&mut self) {
+    // A forced reload skips the cache and always fetches from the API
+    fn reload_schedule(&mut self, force: bool) {
         let slugs = self.leagues.get_selected_ids();
+        self.fetch_schedules(slugs, force);
+    }
+
+    fn fetch_schedules(&mut self, slugs: Vec<String>, force: bool) {
         if slugs.is_empty() {
             return;
         }
@@ -145,87 +344,1269 @@ impl App {
         let sender = self.events.get_sender_clone();
         let resources = self.resources.clone();
 
+        // Sent together, so loading many leagues updates the schedule once
         tokio::spawn(async move {
-            for slug in slugs {
-                match resources.get_schedule(&slug).await {
-                    Some(events) => sender
-                        .send(Event::App(AppEvent::RecieveSchedule((slug, events))))
-                        .unwrap(),
-                    None => {}
-                };
+            let schedules = get_schedules(&resources, slugs, force).await;
+            if !schedules.is_empty() {
+                sender
+                    .send(Event::App(AppEvent::RecieveSchedules(schedules)))
+                    .unwrap()
+            }
+        });
+    }
+
+    // Activates the startup leagues and shows their cached schedules before anything is
+    // fetched. Both happen in one task, so the cache can't replace newer data
+    fn preload_schedules(&mut self, slugs: Vec<String>) {
+        for slug in &slugs {
+            self.schedule.set_active(slug.to_string());
+        }
+        if slugs.is_empty() {
+            return;
+        }
+
+        let sender = self.events.get_sender_clone();
+        let resources = self.resources.clone();
+        let automatic_reload = self.config.automatic_reload;
+
+        tokio::spawn(async move {
+            let cached: Vec<(String, Vec<models::Event>)> =
+                join_all(slugs.iter().map(|slug| resources.get_cached_schedule(slug)))
+                    .await
+                    .into_iter()
+                    .zip(&slugs)
+                    .filter_map(|(events, slug)| Some((slug.clone(), events?)))
+                    .collect();
+            if !cached.is_empty() {
+                info!("Preloaded {} cached schedules", cached.len());
+                let _ = sender.send(Event::App(AppEvent::RecieveSchedules(cached)));
+            }
+            if !automatic_reload {
+                return;
+            }
+            let schedules = get_schedules(&resources, slugs, false).await;
+            if !schedules.is_empty() {
+                let _ = sender.send(Event::App(AppEvent::RecieveSchedules(schedules)));
+            }
+        });
+    }
+
+    // Tells what a refresh changed, instead of silently swapping the schedule. The first
+    // schedule of a league has nothing to compare to
+    fn report_changes(&mut self, slug: &str, events: &[models::Event]) {
+        let old = self.schedule.league_events(slug);
+        if old.is_empty() {
+            return;
+        }
+        let now = self.clock.now();
+        let changes = ScheduleChanges::between(&old, events, now);
+        if changes.is_empty() {
+            return;
+        }
+        let league = events
+            .first()
+            .or(old.first().copied())
+            .map_or(slug.to_string(), |e| e.league_name.to_string());
+        let changes = self.config.strings.changes(&changes);
+        let message = format!("{}: {}", league, changes);
+        info!("Schedule changed, {}", message);
+        let notifications = &self.config.notifications;
+        if notifications.enabled
+            && notifications.schedule_changes
+            && !notifications.defaults.is_quiet(now.time())
+        {
+            notify::send(&format!("{} schedule changed", league), &changes);
+        }
+        self.toast(ToastLevel::Info, message);
+    }
+
+    // Opens a popup with the games of the selected match, which are fetched in the background
+    fn show_details(&mut self) {
+        let Some(event) = self.schedule.selected_event(&self.schedule_state) else {
+            return;
+        };
+        let mut details = Details::new(
+            event.clone(),
+            self.schedule_state.spoil_results,
+            self.config.clone(),
+        );
+        details.set_head_to_head(self.schedule.head_to_head(event));
+        // Without games there is nothing to fetch, only the estimated end is shown
+        if matches!(event.state, MatchState::Unstarted(_)) {
+            details.set_games(Ok(Vec::new()));
+            self.details = Some(details);
+            return;
+        }
+        if event.match_id.is_empty() {
+            self.toast(
+                ToastLevel::Warning,
+                "Match has no id, refresh the schedule and try again".to_string(),
+            );
+            return;
+        }
+
+        let match_id = event.match_id.clone();
+        self.details = Some(details);
+        let sender = self.events.get_sender_clone();
+        let resources = self.resources.clone();
+        tokio::spawn(async move {
+            let games = match resources.fetch_event_details(&match_id).await {
+                Ok(details) => {
+                    let mut games = Vec::new();
+                    let mut played: Vec<_> = details
+                        .match_field
+                        .games
+                        .iter()
+                        .filter(|g| g.state == "completed" || g.state == "inProgress")
+                        .collect();
+                    played.sort_by_key(|g| g.number);
+                    for game in played {
+                        // Games without live data, Ex: forfeits, are still listed
+                        let window = match resources.fetch_game_window(&game.id).await {
+                            Ok(window) => Some(window),
+                            Err(e) => {
+                                warn!("Failed to fetch game window '{}': {:?}", game.id, e);
+                                None
+                            }
+                        };
+                        let team = |team: Option<&str>, side: &str| {
+                            team.and_then(|id| details.match_field.team_code(id))
+                                .unwrap_or(side)
+                                .to_string()
+                        };
+                        let metadata = window.as_ref().map(|w| &w.game_metadata);
+                        let teams = [
+                            team(
+                                metadata.map(|m| m.blue_team_metadata.esports_team_id.as_str()),
+                                "Blue",
+                            ),
+                            team(
+                                metadata.map(|m| m.red_team_metadata.esports_team_id.as_str()),
+                                "Red",
+                            ),
+                        ];
+                        games.push(GameDetails {
+                            id: game.id.clone(),
+                            number: game.number,
+                            state: game.state.clone(),
+                            teams,
+                            window,
+                        });
+                    }
+                    Ok(games)
+                }
+                Err(e) => {
+                    error!("Failed to fetch event details '{}': {:?}", match_id, e);
+                    Err(format!("Failed to fetch match details: {}", e))
+                }
+            };
+            let _ = sender.send(Event::App(AppEvent::RecieveDetails(match_id, games)));
+        });
+    }
+
+    // Fetches the player stats of the game selected in the detail popup, once per game
+    fn fetch_player_stats(&mut self) {
+        let Some((game_id, finished)) = self.details.as_mut().and_then(|d| d.request_stats())
+        else {
+            return;
+        };
+        let sender = self.events.get_sender_clone();
+        let resources = self.resources.clone();
+        tokio::spawn(async move {
+            let stats = resources
+                .get_player_stats(&game_id, finished)
+                .await
+                .map_err(|e| {
+                    error!("Failed to fetch player stats '{}': {:?}", game_id, e);
+                    format!("Failed to fetch player stats: {}", e)
+                });
+            let _ = sender.send(Event::App(AppEvent::RecieveStats(game_id, stats)));
+        });
+    }
+
+    // Opens the stream or VOD of the selected match in the browser, or in the player if `play`
+    fn open_link(&mut self, play: bool) {
+        let Some(event) = self.schedule.selected_event(&self.schedule_state).cloned() else {
+            return;
+        };
+        self.open_event_link(&event, play);
+    }
+
+    fn open_event_link(&mut self, event: &models::Event, play: bool) {
+        let live = match event.state {
+            MatchState::InProgress(_) => true,
+            MatchState::Completed(_) => false,
+            _ => {
+                self.toast(ToastLevel::Info, "Match has not started yet".to_string());
+                return;
             }
+        };
+        if event.match_id.is_empty() {
+            self.toast(
+                ToastLevel::Warning,
+                "Match has no id, refresh the schedule and try again".to_string(),
+            );
+            return;
+        }
+
+        let match_id = event.match_id.clone();
+        let locale = self.config.stream_locale.clone();
+        let sender = self.events.get_sender_clone();
+        let resources = self.resources.clone();
+        tokio::spawn(async move {
+            let report = |app_event| {
+                let _ = sender.send(Event::App(app_event));
+            };
+            let details = match resources.fetch_event_details(&match_id).await {
+                Ok(details) => details,
+                Err(e) => {
+                    error!("Failed to fetch event details '{}': {:?}", match_id, e);
+                    report(AppEvent::Error(format!(
+                        "Failed to fetch match details: {}",
+                        e
+                    )));
+                    return;
+                }
+            };
+            let media = match live {
+                true => details.stream(&locale),
+                false => details.vod(&locale),
+            };
+            let url = match (media, live) {
+                (Some(media), _) => media.url(),
+                (None, true) if !play => "https://lolesports.com/live".to_string(),
+                (None, true) => {
+                    report(AppEvent::Warning("No stream found for match".to_string()));
+                    return;
+                }
+                (None, false) => {
+                    report(AppEvent::Warning("No VOD found for match".to_string()));
+                    return;
+                }
+            };
+            if play {
+                report(AppEvent::LaunchPlayer(url));
+                return;
+            }
+            info!("Opening '{}'", url);
+            if let Err(e) = open::that_detached(&url) {
+                error!("Failed to open '{}': {:?}", url, e);
+                report(AppEvent::Error(format!("Failed to open browser: {}", e)));
+            }
+        });
+    }
+
+    fn show_queue(&mut self) {
+        let favorites_only = !self.config.favorite_teams.is_empty();
+        self.queue = Some(Queue::new(favorites_only, self.config.clone()));
+        self.update_queue();
+    }
+
+    fn update_queue(&mut self) {
+        if let Some(queue) = self.queue.as_mut() {
+            queue.set_events(self.schedule.vod_queue(queue.favorites_only));
+        }
+    }
+
+    fn open_queued(&mut self, play: bool) {
+        if let Some(event) = self.queue.as_ref().and_then(|q| q.selected()).cloned() {
+            self.open_event_link(&event, play);
+        }
+    }
+
+    fn mark_queued_watched(&mut self) {
+        let Some(match_id) = self
+            .queue
+            .as_ref()
+            .and_then(|q| q.selected())
+            .map(|e| e.match_id.clone())
+        else {
+            return;
+        };
+        self.set_watched(&match_id, true);
+        self.update_queue();
+    }
+
+    // Only completed matches have a VOD to watch
+    fn toggle_watched(&mut self) {
+        let Some(event) = self.schedule.selected_event(&self.schedule_state) else {
+            return;
+        };
+        if !matches!(event.state, MatchState::Completed(_)) {
+            self.toast(ToastLevel::Info, "Match has not finished yet".to_string());
+            return;
+        }
+        let match_id = event.match_id.clone();
+        let watched = !self.schedule.is_watched(&match_id);
+        self.set_watched(&match_id, watched);
+    }
+
+    fn set_watched(&mut self, match_id: &str, watched: bool) {
+        if let Err(e) = self.schedule.set_watched(match_id, watched) {
+            error!("Failed to save watched matches: {}", e);
+            self.toast(
+                ToastLevel::Error,
+                format!("Failed to save watched matches: {}", e),
+            );
+        }
+    }
+
+    fn toast(&mut self, level: ToastLevel, message: String) {
+        if level == ToastLevel::Error {
+            self.last_error = Some(message.clone());
+        }
+        self.toasts.push(level, message);
+        self.redraw = true;
+        let sender = self.events.get_sender_clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(TOAST_DURATION).await;
+            let _ = sender.send(Event::App(AppEvent::ExpireToasts));
         });
     }
 
-    fn set_active(&mut self, slug: String) {
-        self.schedule.set_active(slug);
+    // Time based updates, sets `redraw` if anything visible changed
+    fn handle_tick(&mut self) {
+        self.update_title();
+        self.update_summary();
+        // Notifications are not limited to the shown date range
+        let events: Vec<_> = self.schedule.active_events().collect();
+        self.notifier.check(&events);
+        if let Some(interval) = self.config.refresh_interval
+            && self.last_refresh.elapsed() >= interval
+        {
+            self.events.send(AppEvent::RefreshSchedule);
+        }
+        // Only leagues with a match in progress are refetched, to spare the API
+        if let Some(interval) = self.config.live_refresh_interval
+            && self.last_live_refresh.elapsed() >= interval
+        {
+            self.last_live_refresh = Instant::now();
+            let live = self.schedule.live_leagues();
+            if !live.is_empty() {
+                info!("Refreshing live leagues: {:?}", live);
+                self.fetch_schedules(live, true);
+            }
+        }
+    }
+
+    // Activates the leagues, fetching their schedules in one batch
+    fn set_active(&mut self, slugs: Vec<String>) {
+        for slug in &slugs {
+            self.schedule.set_active(slug.to_string());
+        }
         if self.config.automatic_reload {
-            self.reload_schedule();
+            self.fetch_schedules(slugs, false);
         }
     }
 
+    fn set_selected_ids(&mut self, ids: &[String]) {
+        let mut added = Vec::new();
+        for (selected, id) in self.leagues.set_selected_ids(ids) {
+            match selected {
+                true => added.push(id),
+                false => self.schedule.unset_active(&id),
+            }
+        }
+        self.set_active(added);
+    }
+
     pub async fn run(mut self, mut terminal: DefaultTerminal) -> color_eyre::Result<()> {
+        self.events.listen();
+        if self.config.terminal_title {
+            // Saves the current title on the terminals title stack
+            let _ = execute!(stdout(), Print("\x1b[22;0t"));
+        }
+        let frame_time = self
+            .config
+            .max_fps
+            .map_or(Duration::ZERO, |fps| Duration::from_secs(1) / fps);
+        let mut last_frame: Option<Instant> = None;
         while self.running {
-            terminal.draw(|frame| self.draw(frame, frame.area()))?;
-            match self.events.next().await? {
+            // Changes arriving faster than the frame rate are drawn in the next frame
+            let wait = last_frame.map_or(Duration::ZERO, |last| {
+                frame_time.saturating_sub(last.elapsed())
+            });
+            if self.redraw && wait.is_zero() {
+                terminal.draw(|frame| self.draw(frame, frame.area()))?;
+                self.redraw = false;
+                last_frame = Some(Instant::now());
+            }
+            let event = if self.redraw {
+                tokio::select! {
+                    event = self.events.next() => event?,
+                    _ = tokio::time::sleep(wait) => continue,
+                }
+            } else {
+                self.events.next().await?
+            };
+            // Events that only start work, like fetching, redraw once their results arrive
+            if let Event::App(app_event) = &event {
+                self.redraw |= !matches!(
+                    app_event,
+                    AppEvent::Quit
+                        | AppEvent::Up
+                        | AppEvent::Down
+                        | AppEvent::Left
+                        | AppEvent::Right
+                        | AppEvent::FocusNext
+                        | AppEvent::FocusPrev
+                        | AppEvent::OpenLink
+                        | AppEvent::WatchStream
+                        | AppEvent::LaunchPlayer(_)
+                        | AppEvent::ReloadLeagues
+                        | AppEvent::ReloadSchedule
+                        | AppEvent::RefreshSchedule
+                        | AppEvent::ExpireToasts
+                        | AppEvent::Suspend
+                        | AppEvent::ToggleMouse
+                );
+            }
+            match event {
+                Event::Tick => self.handle_tick(),
                 Event::Crossterm(event) => match event {
                     crossterm::event::Event::Key(key_event) => self.handle_key_events(key_event)?,
+                    crossterm::event::Event::Mouse(mouse_event) => match mouse_event.kind {
+                        MouseEventKind::ScrollUp => self.handle_up(),
+                        MouseEventKind::ScrollDown => self.handle_down(),
+                        MouseEventKind::Down(MouseButton::Left) => {
+                            self.handle_click(mouse_event.column, mouse_event.row)
+                        }
+                        _ => {}
+                    },
+                    crossterm::event::Event::Resize(_, _) => self.redraw = true,
                     _ => {}
                 },
-                Event::App(app_event) => match app_event {
-                    AppEvent::Quit => self.quit(),
-                    AppEvent::Up => self.handle_up(),
-                    AppEvent::Down => self.handle_down(),
-                    AppEvent::Left => self.handle_left(),
-                    AppEvent::Right => self.handle_right(),
-                    AppEvent::Select => self.handle_select(),
-
-                    AppEvent::GotoToday => {
-                        self.schedule_state.select_today(&self.schedule);
-                        self.mode = Mode::Events;
-                        self.schedule_state.focused = true;
-                        self.leagues_state.focused = false;
-                    }
-                    AppEvent::ToggleSpoilResults => {
-                        self.schedule_state.spoil_results = !self.schedule_state.spoil_results
-                    }
-                    AppEvent::ToggleSpoilMatches => {
-                        self.schedule_state.spoil_matches = !self.schedule_state.spoil_matches
-                    }
+                Event::App(app_event) => self.handle_app_event(app_event, &mut terminal).await?,
+            }
+        }
+        if self.mouse_capture {
+            self.set_mouse_capture(false);
+        }
+        if self.config.terminal_title {
+            let _ = execute!(stdout(), Print("\x1b[23;0t"));
+        }
+        if let Some(path) = self.config.socket_path() {
+            let _ = std::fs::remove_file(path);
+        }
+        Ok(())
+    }
+
+    async fn handle_app_event(
+        &mut self,
+        app_event: AppEvent,
+        terminal: &mut DefaultTerminal,
+    ) -> color_eyre::Result<()> {
+        match app_event {
+            AppEvent::Quit => self.quit(),
+            AppEvent::Up => self.handle_up(),
+            AppEvent::Down => self.handle_down(),
+            AppEvent::Left => self.handle_left(),
+            AppEvent::Right => self.handle_right(),
+            AppEvent::Select => self.handle_select(),
+            AppEvent::Undo => self.undo_league_selection(),
+            AppEvent::FocusNext => self.cycle_focus(1),
+            AppEvent::FocusPrev => self.cycle_focus(-1),
+
+            AppEvent::GotoToday => {
+                self.schedule_state.select_today(&self.schedule);
+                self.focus_events();
+            }
+            AppEvent::GotoFavorite => {
+                self.schedule_state.select_favorite(&self.schedule);
+                self.focus_events();
+            }
+            AppEvent::GotoLive => {
+                if self.schedule_state.select_live(&self.schedule) {
+                    self.focus_events();
+                } else {
+                    self.toast(ToastLevel::Info, "No match in progress".to_string());
+                }
+            }
+            AppEvent::RemindMatch => {
+                let mut command_line = CommandLine::new(self.config.clone());
+                command_line.input = "remind ".to_string();
+                self.command_line = Some(command_line);
+            }
+            AppEvent::ShowDetails => self.show_details(),
+            AppEvent::ShowQueue => self.show_queue(),
+            AppEvent::ShowHealth => {
+                self.health = Some(Health::new(self.resources.failures(), self.config.clone()))
+            }
+            AppEvent::ToggleWatched => self.toggle_watched(),
+            AppEvent::RecieveDetails(match_id, games) => {
+                if let Some(details) = self.details.as_mut().filter(|d| d.match_id() == match_id) {
+                    details.set_games(games);
+                }
+                self.fetch_player_stats();
+            }
+            AppEvent::RecieveStats(game_id, stats) => {
+                if let Some(details) = self.details.as_mut() {
+                    details.set_stats(&game_id, stats);
+                }
+            }
+            AppEvent::ToggleFold => self.schedule_state.toggle_fold(&self.schedule),
+            AppEvent::CycleDateRange => self.set_date_range(self.schedule.date_range.next()),
+            AppEvent::CycleLeagueSort => self.set_league_sort(self.leagues.sort.next()),
+            AppEvent::ToggleUpcomingLeagues => self.toggle_upcoming_leagues(),
+            AppEvent::ActivateGroup(name) => self.activate_group(&name),
+            AppEvent::OpenLink => self.open_link(false),
+            AppEvent::WatchStream => self.open_link(true),
+            AppEvent::LaunchPlayer(url) => self.launch_player(&url, terminal).await?,
+            AppEvent::ToggleSpoilResults => {
+                self.schedule_state.spoil_results = !self.schedule_state.spoil_results;
+                if let Some(details) = self.details.as_mut() {
+                    details.spoil_results = self.schedule_state.spoil_results;
+                }
+                self.fetch_player_stats();
+            }
+            AppEvent::ToggleSpoilMatches => {
+                self.schedule_state.spoil_matches = !self.schedule_state.spoil_matches
+            }
 
-                    AppEvent::ReloadLeagues => self.reload_leagues(),
-                    AppEvent::RecieveLeagues(l) => {
-                        self.leagues.set_leagues(l);
-                        if !self.leagues.leagues.is_empty() {
-                            self.leagues_state.list_state.select_first();
-                            let default_leagues = self.config.default_leagues.clone();
-                            for name in &default_leagues {
-                                match self.leagues.select_name(name) {
-                                    Some(id) => self.set_active(id),
-                                    None => warn!("Could not find default league '{}'", name),
-                                }
+            AppEvent::ReloadLeagues => self.reload_leagues(),
+            AppEvent::RecieveLeagues(l) => {
+                self.leagues.set_leagues(l);
+                if !self.leagues.is_empty() {
+                    self.leagues
+                        .select_first(&mut self.leagues_state.list_state);
+                    let default_leagues = self.config.default_leagues.clone();
+                    let mut missing = Vec::new();
+                    let mut ids = Vec::new();
+                    for name in &default_leagues {
+                        match self.leagues.select_name(name) {
+                            Some(id) => ids.push(id),
+                            None => {
+                                warn!("Could not find default league '{}'", name);
+                                missing.push(name.as_str());
                             }
                         }
                     }
-                    AppEvent::ReloadSchedule => self.reload_schedule(),
-                    AppEvent::RecieveSchedule((slug, events)) => {
-                        self.schedule.add_events(slug, events);
-                        self.schedule_state.select_today(&self.schedule);
+                    self.preload_schedules(ids);
+                    if !missing.is_empty() {
+                        self.toast(
+                            ToastLevel::Warning,
+                            format!("Could not find default leagues: {}", missing.join(", ")),
+                        );
                     }
-                },
+                }
+            }
+            AppEvent::RecieveUpcoming(upcoming) => {
+                self.leagues.set_upcoming(upcoming);
+                self.clamp_league_cursor();
+            }
+            AppEvent::ReloadSchedule => self.reload_schedule(false),
+            AppEvent::RefreshSchedule => {
+                self.last_refresh = Instant::now();
+                self.reload_schedule(true);
+            }
+            AppEvent::RecieveSchedules(schedules) => {
+                self.title = None;
+                for (slug, mut events) in schedules {
+                    TeamOverride::apply(&self.config.team_overrides, &mut events);
+                    self.report_changes(&slug, &events);
+                    self.leagues
+                        .update_upcoming(&slug, &events, self.clock.now());
+                    self.schedule.add_events(slug, events);
+                }
+                self.schedule_state.reselect_or_today(&self.schedule);
+                self.update_summary();
+            }
+            AppEvent::ExpireToasts => self.redraw |= self.toasts.expire(),
+            AppEvent::RemoteCommand(command, reply) => {
+                self.last_error = None;
+                match *command {
+                    // Handled here rather than queued, so the reply waits until it ran
+                    Command::Event(app_event) => {
+                        Box::pin(self.handle_app_event(app_event, terminal)).await?
+                    }
+                    command => self.execute_command(command),
+                }
+                let _ = reply.0.send(self.last_error.take().map_or(Ok(()), Err));
+            }
+            AppEvent::Error(message) => self.toast(ToastLevel::Error, message),
+            AppEvent::Warning(message) => self.toast(ToastLevel::Warning, message),
+            AppEvent::CommandMode => {
+                self.command_line = Some(CommandLine::new(self.config.clone()))
             }
+            AppEvent::CommandPalette => self.palette = Some(Palette::new(self.config.clone())),
+            AppEvent::Suspend => self.suspend(terminal)?,
+            AppEvent::ToggleMouse => self.set_mouse_capture(!self.mouse_capture),
         }
         Ok(())
     }
 
+    fn handle_click(&mut self, column: u16, row: u16) {
+        let position = Position::new(column, row);
+        let Some((_, match_id)) = self.live_banner.iter().find(|(a, _)| a.contains(position))
+        else {
+            return;
+        };
+        if self.schedule_state.select_match(&self.schedule, match_id) {
+            self.focus_events();
+        } else {
+            self.toast(
+                ToastLevel::Info,
+                "The match is outside the shown date range".to_string(),
+            );
+        }
+    }
+
+    // Ex: "Today: 4 matches, 1 live, next at 18:00"
+    fn update_summary(&mut self) {
+        if !self.config.summary {
+            return;
+        }
+        let now = self.clock.now();
+        let (count, live, next) = self.schedule.today(now);
+        let strings = &self.config.strings;
+        let mut parts = vec![Strings::count(count, &strings.today_one, &strings.today)];
+        if live > 0 {
+            parts.push(strings.live_now.replace("{}", &live.to_string()));
+        }
+        parts.push(match next {
+            Some(next) if next.date_naive() == now.date_naive() => strings
+                .next_at
+                .replace("{}", &next.format("%H:%M").to_string()),
+            Some(next) => strings
+                .next
+                .replace("{}", &next.format("%a %d %b %H:%M").to_string()),
+            None => strings.nothing_upcoming.to_string(),
+        });
+        let summary = parts.join(", ");
+        if summary != self.summary {
+            self.summary = summary;
+            self.redraw = true;
+        }
+    }
+
+    // Shows the next match in the terminal title, Ex: "lol-cal - T1 vs GEN in 1h 20m"
+    fn update_title(&mut self) {
+        if !self.config.terminal_title {
+            return;
+        }
+        let title = match self.schedule.next_event() {
+            Some(event) => {
+                let (team0, team1) = match event.state {
+                    MatchState::Unstarted(_) if !self.schedule_state.spoil_matches => (
+                        self.config.spoiler_mask.as_str(),
+                        self.config.spoiler_mask.as_str(),
+                    ),
+                    _ => (event.teams[0].short.as_str(), event.teams[1].short.as_str()),
+                };
+                let when = match event.state {
+                    MatchState::InProgress(_) => "live".to_string(),
+                    _ => format_countdown(event.start_time - self.clock.now()),
+                };
+                format!("lol-cal - {} vs {} {}", team0, team1, when)
+            }
+            None => "lol-cal".to_string(),
+        };
+        if self.title.as_ref() != Some(&title) {
+            if let Err(e) = execute!(stdout(), SetTitle(&title)) {
+                error!("Failed to set terminal title: {}", e);
+            }
+            self.title = Some(title);
+        }
+    }
+
     pub fn handle_key_events(&mut self, key_event: KeyEvent) -> color_eyre::Result<()> {
+        if self.command_line.is_some() {
+            self.redraw = true;
+            self.handle_command_key(key_event);
+            return Ok(());
+        }
+        if self.palette.is_some() {
+            self.redraw = true;
+            self.handle_palette_key(key_event);
+            return Ok(());
+        }
+        if self.details.is_some() {
+            self.redraw = true;
+            self.handle_details_key(key_event);
+            return Ok(());
+        }
+        if self.queue.is_some() {
+            self.redraw = true;
+            self.handle_queue_key(key_event);
+            return Ok(());
+        }
+        if self.health.is_some() {
+            self.redraw = true;
+            self.handle_health_key(key_event);
+            return Ok(());
+        }
+        if self.config.type_ahead
+            && self.mode == Mode::Leagues
+            && !key_event
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+            && let KeyCode::Char(c) = key_event.code
+            && c.is_alphanumeric()
+            // Keybindings keep working, only unbound keys are typed
+            && self.config.keybindings.get(&key_event).is_none()
+        {
+            self.type_ahead(c);
+            return Ok(());
+        }
+        if let Some(app_event) = self.config.keybindings.get(&key_event) {
+            self.events.send(app_event.clone())
+        };
+        Ok(())
+    }
+
+    fn handle_command_key(&mut self, key_event: KeyEvent) {
+        let Some(command_line) = self.command_line.as_mut() else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Esc => self.command_line = None,
+            KeyCode::Enter => {
+                let input = std::mem::take(&mut command_line.input);
+                self.command_line = None;
+                match command::parse(&input) {
+                    Ok(command) => self.execute_command(command),
+                    Err(e) => self.toast(ToastLevel::Error, e),
+                }
+            }
+            // Backspace on an empty line leaves command mode, like in vim
+            KeyCode::Backspace if command_line.pop().is_none() => self.command_line = None,
+            KeyCode::Tab => {
+                if let Some(completed) = command::complete(&command_line.input) {
+                    command_line.input = completed;
+                }
+            }
+            KeyCode::Char(c) => command_line.push(c),
+            _ => {}
+        }
+    }
+
+    fn handle_palette_key(&mut self, key_event: KeyEvent) {
+        let Some(palette) = self.palette.as_mut() else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Esc => self.palette = None,
+            KeyCode::Enter => {
+                if let Some(app_event) = palette.selected() {
+                    self.events.send(app_event);
+                }
+                self.palette = None;
+            }
+            KeyCode::Up => palette.up(),
+            KeyCode::Down | KeyCode::Tab => palette.down(),
+            KeyCode::Backspace => palette.pop(),
+            KeyCode::Char(c) => palette.push(c),
+            _ => {}
+        }
+    }
+
+    // The spoiler toggles still work, so picks can be revealed without closing the popup
+    fn handle_details_key(&mut self, key_event: KeyEvent) {
+        let Some(details) = self.details.as_mut() else {
+            return;
+        };
+        match self.config.keybindings.get(&key_event) {
+            Some(AppEvent::Up) => details.up(),
+            Some(AppEvent::Down) => details.down(),
+            Some(AppEvent::Left) => {
+                details.select_previous();
+                self.fetch_player_stats();
+            }
+            Some(AppEvent::Right) => {
+                details.select_next();
+                self.fetch_player_stats();
+            }
+            Some(AppEvent::ToggleSpoilResults) => self.events.send(AppEvent::ToggleSpoilResults),
+            Some(AppEvent::Quit | AppEvent::ShowDetails) => self.details = None,
+            _ if key_event.code == KeyCode::Esc => self.details = None,
+            _ => {}
+        }
+    }
+
+    fn handle_health_key(&mut self, key_event: KeyEvent) {
+        let Some(health) = self.health.as_mut() else {
+            return;
+        };
         match self.config.keybindings.get(&key_event) {
-            Some(app_event) => self.events.send(app_event.clone()),
-            None => {}
+            Some(AppEvent::Up) => health.up(),
+            Some(AppEvent::Down) => health.down(),
+            Some(AppEvent::Quit | AppEvent::ShowHealth) => self.health = None,
+            _ if key_event.code == KeyCode::Esc => self.health = None,
+            _ => {}
+        }
+    }
+
+    // Typing starts over after a pause, like in file managers
+    fn type_ahead(&mut self, c: char) {
+        const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_secs(1);
+        let (typed, last) = &mut self.type_ahead;
+        if last.elapsed() > TYPE_AHEAD_TIMEOUT {
+            typed.clear();
+        }
+        typed.push(c);
+        *last = Instant::now();
+        if let Some(row) = self.leagues.find_prefix(typed) {
+            self.redraw |= self.leagues_state.list_state.selected() != Some(row);
+            self.leagues_state.list_state.select(Some(row));
+        }
+    }
+
+    fn handle_queue_key(&mut self, key_event: KeyEvent) {
+        let Some(queue) = self.queue.as_mut() else {
+            return;
+        };
+        match self.config.keybindings.get(&key_event) {
+            Some(AppEvent::Up) => queue.up(),
+            Some(AppEvent::Down) => queue.down(),
+            Some(AppEvent::OpenLink) => self.open_queued(false),
+            Some(AppEvent::WatchStream) => self.open_queued(true),
+            Some(AppEvent::ToggleWatched) => self.mark_queued_watched(),
+            Some(AppEvent::GotoFavorite) => {
+                queue.favorites_only = !queue.favorites_only;
+                self.update_queue();
+            }
+            Some(AppEvent::Quit | AppEvent::ShowQueue) => self.queue = None,
+            _ if key_event.code == KeyCode::Esc => self.queue = None,
+            _ => {}
+        }
+    }
+
+    pub fn execute_command(&mut self, command: Command) {
+        match command {
+            Command::Quit => self.quit(),
+            Command::GotoToday => {
+                self.schedule_state.select_today(&self.schedule);
+                self.focus_events();
+            }
+            Command::Goto(date) => {
+                self.schedule_state.select_date(&self.schedule, date);
+                self.focus_events();
+            }
+            Command::League(action, name) => {
+                self.save_league_selection();
+                let select = match action {
+                    LeagueAction::Add => true,
+                    LeagueAction::Remove => false,
+                    LeagueAction::Toggle => !self.leagues.is_name_selected(&name).unwrap_or(false),
+                };
+                let id = if select {
+                    self.leagues.select_name(&name)
+                } else {
+                    self.leagues.deselect_name(&name)
+                };
+                match id {
+                    Some(id) if select => {
+                        self.leagues
+                            .add_recent(&id, &mut self.leagues_state.list_state);
+                        self.set_active(vec![id])
+                    }
+                    Some(id) => self.schedule.unset_active(&id),
+                    None => {
+                        self.toast(
+                            ToastLevel::Error,
+                            format!("Could not find league '{}'", name),
+                        );
+                        return;
+                    }
+                }
+                self.schedule_state.reselect_or_today(&self.schedule);
+            }
+            Command::Region(action, region) => {
+                let hide = match action {
+                    RegionAction::Hide => true,
+                    RegionAction::Show => false,
+                    RegionAction::Toggle => !self.leagues.is_region_hidden(&region),
+                };
+                match self.leagues.set_region_hidden(&region, hide) {
+                    Some(region) => {
+                        self.clamp_league_cursor();
+                        let verb = if hide { "Hiding" } else { "Showing" };
+                        self.toast(ToastLevel::Info, format!("{} region '{}'", verb, region));
+                    }
+                    None => self.toast(
+                        ToastLevel::Error,
+                        format!("Could not find region '{}'", region),
+                    ),
+                }
+            }
+            Command::Spoil(SpoilTarget::Results, toggle) => {
+                self.schedule_state.spoil_results = toggle.apply(self.schedule_state.spoil_results)
+            }
+            Command::Spoil(SpoilTarget::Matches, toggle) => {
+                self.schedule_state.spoil_matches = toggle.apply(self.schedule_state.spoil_matches)
+            }
+            Command::Range(range) => self.set_date_range(range),
+            Command::Remind(before) => self.remind(before),
+            Command::Export {
+                path,
+                favorites,
+                safe,
+            } => self.export(&path, favorites, safe),
+            Command::ExportSelection(path) => self.export_selection(&path),
+            Command::ImportSelection { path, replace } => self.import_selection(&path, replace),
+            Command::Event(app_event) => self.events.send(app_event),
+        }
+    }
+
+    fn remind(&mut self, before: Option<std::time::Duration>) {
+        let Some(event) = self.schedule.selected_event(&self.schedule_state) else {
+            self.toast(ToastLevel::Warning, "No match selected".to_string());
+            return;
         };
+        if event.match_id.is_empty() {
+            self.toast(
+                ToastLevel::Warning,
+                "Match has no id, refresh the schedule and try again".to_string(),
+            );
+            return;
+        }
+        if before.is_some()
+            && (!matches!(event.state, MatchState::Unstarted(_))
+                || event.start_time <= self.clock.now())
+        {
+            self.toast(ToastLevel::Info, "Match has already started".to_string());
+            return;
+        }
+        let event = event.clone();
+        let had_reminder = self.notifier.reminder(&event.match_id).is_some();
+        if let Err(e) = self.notifier.set_reminder(&event, before) {
+            error!("Failed to save reminders: {:?}", e);
+            self.toast(
+                ToastLevel::Error,
+                format!("Failed to save reminders: {}", e),
+            );
+            return;
+        }
+        let message = match before {
+            Some(before) => format!("Reminding {} before the match", format_length(before)),
+            None if had_reminder => "Removed the reminder".to_string(),
+            None => "Match has no reminder".to_string(),
+        };
+        self.toast(ToastLevel::Info, message);
+    }
+
+    fn set_date_range(&mut self, range: DateRange) {
+        self.schedule.date_range = range;
+        self.schedule_state.reselect_or_today(&self.schedule);
+        let shown = match range {
+            DateRange::All => "all matches",
+            DateRange::Upcoming => "upcoming matches",
+            DateRange::Today => "matches of today",
+            DateRange::Week => "matches of the next 7 days",
+            DateRange::Weekend => "matches of the weekend",
+        };
+        self.toast(ToastLevel::Info, format!("Showing {}", shown));
+    }
+
+    // Keeps the cursor on a league after leagues were hidden
+    fn clamp_league_cursor(&mut self) {
+        self.leagues.clamp(&mut self.leagues_state.list_state);
+    }
+
+    fn toggle_upcoming_leagues(&mut self) {
+        let upcoming_only = !self.leagues.upcoming_only;
+        self.leagues.set_upcoming_only(upcoming_only);
+        self.clamp_league_cursor();
+        let message = match upcoming_only {
+            true => "Hiding leagues without upcoming matches",
+            false => "Showing all leagues",
+        };
+        self.toast(ToastLevel::Info, message.to_string());
+    }
+
+    // The cursor stays on the same league
+    fn set_league_sort(&mut self, sort: LeagueSort) {
+        let list_state = &mut self.leagues_state.list_state;
+        let cursor = list_state
+            .selected()
+            .and_then(|i| self.leagues.league_at(i))
+            .map(|l| l.id.clone());
+        self.leagues.set_sort(sort);
+        if let Some(i) = cursor.and_then(|id| self.leagues.position(&id)) {
+            list_state.select(Some(i));
+        }
+        self.toast(
+            ToastLevel::Info,
+            format!("Sorting leagues by {}", sort.name()),
+        );
+    }
+
+    fn export_selection(&mut self, path: &Path) {
+        let slugs = self.leagues.get_selected_slugs();
+        let mut content = format!("# {} league selection\n", env!("CARGO_PKG_NAME"));
+        for slug in &slugs {
+            content.push_str(slug);
+            content.push('\n');
+        }
+        match std::fs::write(path, content) {
+            Ok(_) => self.toast(
+                ToastLevel::Info,
+                format!("Exported {} leagues to {}", slugs.len(), path.display()),
+            ),
+            Err(e) => {
+                error!("Failed to export selection to {:?}: {:?}", path, e);
+                self.toast(
+                    ToastLevel::Error,
+                    format!("Failed to export selection: {}", e),
+                );
+            }
+        }
+    }
+
+    // Lines are league names, slugs or ids, empty lines and `#` comments are skipped
+    fn import_selection(&mut self, path: &Path, replace: bool) {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                error!("Failed to import selection from {:?}: {:?}", path, e);
+                self.toast(
+                    ToastLevel::Error,
+                    format!("Failed to import selection: {}", e),
+                );
+                return;
+            }
+        };
+        let mut ids = match replace {
+            true => Vec::new(),
+            false => self.leagues.get_selected_ids(),
+        };
+        let mut missing = Vec::new();
+        let mut imported = 0;
+        for name in content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        {
+            match self.leagues.find_id(name) {
+                Some(id) => {
+                    imported += 1;
+                    if !ids.contains(&id) {
+                        ids.push(id);
+                    }
+                }
+                None => missing.push(name),
+            }
+        }
+
+        self.save_league_selection();
+        self.set_selected_ids(&ids);
+        self.schedule_state.reselect_or_today(&self.schedule);
+        self.toast(
+            ToastLevel::Info,
+            format!("Imported {} leagues from {}", imported, path.display()),
+        );
+        if !missing.is_empty() {
+            self.toast(
+                ToastLevel::Warning,
+                format!("Could not find leagues: {}", missing.join(", ")),
+            );
+        }
+    }
+
+    // Replaces the selection with the leagues of the group, which can be undone
+    fn activate_group(&mut self, name: &str) {
+        let Some((name, leagues)) = self.config.league_group(name).cloned() else {
+            self.toast(
+                ToastLevel::Error,
+                format!("Could not find group '{}'", name),
+            );
+            return;
+        };
+        let mut ids = Vec::new();
+        let mut missing = Vec::new();
+        for league in &leagues {
+            match self.leagues.find_id(league) {
+                Some(id) if !ids.contains(&id) => ids.push(id),
+                Some(_) => {}
+                None => missing.push(league.as_str()),
+            }
+        }
+
+        self.save_league_selection();
+        self.set_selected_ids(&ids);
+        self.schedule_state.reselect_or_today(&self.schedule);
+        self.toast(
+            ToastLevel::Info,
+            format!("Activated group '{}' with {} leagues", name, ids.len()),
+        );
+        if !missing.is_empty() {
+            self.toast(
+                ToastLevel::Warning,
+                format!("Could not find leagues: {}", missing.join(", ")),
+            );
+        }
+    }
+
+    fn export(&mut self, path: &Path, favorites: bool, safe: bool) {
+        let mut events: Vec<_> = self
+            .schedule
+            .filtered()
+            .into_iter()
+            .filter(|e| !favorites || e.teams.iter().any(|t| self.config.is_favorite(&t.short)))
+            .cloned()
+            .collect();
+        // Exports hide the same things as the schedule currently does
+        let spoilers = Spoilers {
+            results: self.schedule_state.spoil_results && !safe,
+            matches: self.schedule_state.spoil_matches && !safe,
+            mask: &self.config.spoiler_mask,
+            scope: self.config.spoiler_mask_scope,
+        };
+        let content = match path.extension().and_then(|e| e.to_str()) {
+            Some("ics") => export::to_ics(&events, spoilers, &self.config, self.clock.now()),
+            Some("md") => {
+                events = export::next_week(events, self.clock.now());
+                export::to_markdown(&events, spoilers, self.clock.now())
+            }
+            _ => match export::to_json(&events, spoilers) {
+                Ok(json) => json,
+                Err(e) => {
+                    self.toast(ToastLevel::Error, format!("Failed to export: {}", e));
+                    return;
+                }
+            },
+        };
+        match std::fs::write(path, content) {
+            Ok(_) => self.toast(
+                ToastLevel::Info,
+                format!("Exported {} matches to {}", events.len(), path.display()),
+            ),
+            Err(e) => {
+                error!("Failed to export to '{}': {:?}", path.display(), e);
+                self.toast(ToastLevel::Error, format!("Failed to export: {}", e));
+            }
+        }
+    }
+
+    pub fn set_mouse_capture(&mut self, enabled: bool) {
+        let result = if enabled {
+            execute!(stdout(), EnableMouseCapture)
+        } else {
+            execute!(stdout(), DisableMouseCapture)
+        };
+        match result {
+            Ok(_) => self.mouse_capture = enabled,
+            Err(e) => {
+                error!("Failed to set mouse capture: {}", e);
+                self.toast(
+                    ToastLevel::Error,
+                    format!("Failed to set mouse capture: {}", e),
+                );
+            }
+        }
+    }
+
+    // Hands the terminal back to the shell until the process is resumed with `fg`
+    #[cfg(unix)]
+    fn suspend(&mut self, terminal: &mut DefaultTerminal) -> color_eyre::Result<()> {
+        let mouse_capture = self.leave_terminal();
+        // SAFETY: raising a signal on the current process has no memory safety concerns
+        unsafe {
+            libc::raise(libc::SIGTSTP);
+        }
+        self.enter_terminal(terminal, mouse_capture)
+    }
+
+    // Restores the terminal for another program, returns whether the mouse was captured
+    fn leave_terminal(&mut self) -> bool {
+        let mouse_capture = self.mouse_capture;
+        if mouse_capture {
+            self.set_mouse_capture(false);
+        }
+        ratatui::restore();
+        mouse_capture
+    }
+
+    fn enter_terminal(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        mouse_capture: bool,
+    ) -> color_eyre::Result<()> {
+        enable_raw_mode()?;
+        execute!(stdout(), EnterAlternateScreen)?;
+        if mouse_capture {
+            self.set_mouse_capture(true);
+        }
+        terminal.clear()?;
+        self.redraw = true;
         Ok(())
     }
 
+    // Runs the `player` command with `url`, in the terminal if `player_terminal` is set and
+    // detached from it otherwise
+    async fn launch_player(
+        &mut self,
+        url: &str,
+        terminal: &mut DefaultTerminal,
+    ) -> color_eyre::Result<()> {
+        // Quoted, the link is one argument whatever characters it has
+        let args = match self.config.player.contains("{url}") {
+            true => self.config.player.replace("{url}", &hooks::quote(url)),
+            false => format!("{} {}", self.config.player, hooks::quote(url)),
+        };
+        let mut command = hooks::shell(&args);
+        info!("Launching player '{}'", args);
+
+        if self.config.player_terminal {
+            // The player reads the keys while it runs, the app would otherwise take some of them
+            self.events.pause_input(true).await;
+            let mouse_capture = self.leave_terminal();
+            let status = command.status().await;
+            self.enter_terminal(terminal, mouse_capture)?;
+            self.events.pause_input(false).await;
+            match status {
+                Ok(status) if !status.success() => self.toast(
+                    ToastLevel::Warning,
+                    format!("Player exited with {}", status),
+                ),
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Failed to launch player '{}': {:?}", args, e);
+                    self.toast(ToastLevel::Error, format!("Failed to launch player: {}", e));
+                }
+            }
+            return Ok(());
+        }
+
+        command
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null());
+        match command.spawn() {
+            Ok(mut child) => {
+                let sender = self.events.get_sender_clone();
+                tokio::spawn(async move {
+                    if let Ok(status) = child.wait().await
+                        && !status.success()
+                    {
+                        let _ = sender.send(Event::App(AppEvent::Warning(format!(
+                            "Player exited with {}",
+                            status
+                        ))));
+                    }
+                });
+            }
+            Err(e) => {
+                error!("Failed to launch player '{}': {:?}", args, e);
+                self.toast(ToastLevel::Error, format!("Failed to launch player: {}", e));
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn suspend(&mut self, _terminal: &mut DefaultTerminal) -> color_eyre::Result<()> {
+        self.toast(
+            ToastLevel::Warning,
+            "Suspending is only supported on Unix".to_string(),
+        );
+        Ok(())
+    }
+
+    fn focus_events(&mut self) {
+        self.focus(Mode::Events);
+    }
+
     pub fn quit(&mut self) {
         self.running = false;
     }
@@ -233,15 +1614,19 @@ impl App {
     fn draw(&mut self, frame: &mut Frame, area: Rect) {
         // let vert_areas = Layout::vertical([Constraint::Max(1), Constraint::Min(0)]).split(area);
         let schedule_min = 56;
-        let mut seperator = 0;
-        let mut league_padding = 3;
-        if self.config.style.border.is_some() {
-            league_padding += 2;
-        } else {
-            seperator = 1;
+        let styles = &self.config.style;
+        // Saturating, as large paddings from the config would overflow
+        let mut league_padding = self.config.padding.saturating_mul(2).saturating_add(3);
+        if styles.leagues_border().is_some() {
+            league_padding = league_padding.saturating_add(2);
         }
+        // Without borders on either side, a line keeps the panes apart
+        let seperator = match (styles.leagues_border(), styles.schedule_border()) {
+            (None, None) => 1,
+            _ => 0,
+        };
         let hor_areas = Layout::horizontal([
-            Constraint::Length(self.leagues.longest + league_padding),
+            Constraint::Length(self.leagues.longest.saturating_add(league_padding)),
             Constraint::Length(seperator),
             Constraint::Min(schedule_min),
         ])
@@ -251,7 +1636,95 @@ impl App {
 
         frame.render_stateful_widget_ref(&self.leagues, hor_areas[0], &mut self.leagues_state);
         frame.render_widget(widget, hor_areas[1]);
-        frame.render_stateful_widget_ref(&self.schedule, hor_areas[2], &mut self.schedule_state);
+        let mut schedule_area = hor_areas[2];
+        if self.config.summary && schedule_area.height > 1 {
+            let summary_area = Rect {
+                height: 1,
+                ..schedule_area
+            };
+            frame.render_widget(
+                Line::from(self.summary.as_str())
+                    .centered()
+                    .style(styles.title()),
+                summary_area,
+            );
+            schedule_area.y += 1;
+            schedule_area.height -= 1;
+        }
+        self.live_banner.clear();
+        let live: Vec<_> = self.schedule.live().collect();
+        if self.config.live_banner && !live.is_empty() && schedule_area.height > 1 {
+            // Ex: "Live: T1 vs GEN game 3  HLE vs KT game 1", the game gives away the score
+            let label = format!(" {}: ", self.config.strings.live);
+            let mut spans = vec![Span::from(label).style(styles.title())];
+            let mut x = schedule_area.x + spans[0].width() as u16;
+            for event in live {
+                let mut text = format!(
+                    "{} {} {}",
+                    event.teams[0].short, self.config.strings.vs, event.teams[1].short
+                );
+                if self.schedule_state.spoil_results
+                    && let Some((wins0, wins1)) = event.result.as_ref().map(|r| r.game_wins)
+                {
+                    let game = (wins0 + wins1 + 1).to_string();
+                    text.push(' ');
+                    text.push_str(&self.config.strings.game.replace("{}", &game));
+                }
+                let span = Span::from(text).style(styles.in_progress.unwrap_or(styles.default));
+                let width = span.width() as u16;
+                self.live_banner.push((
+                    Rect {
+                        x,
+                        y: schedule_area.y,
+                        width,
+                        height: 1,
+                    },
+                    event.match_id.clone(),
+                ));
+                x += width + 2;
+                spans.push(span);
+                spans.push(Span::from("  "));
+            }
+            frame.render_widget(
+                Line::from(spans),
+                Rect {
+                    height: 1,
+                    ..schedule_area
+                },
+            );
+            schedule_area.y += 1;
+            schedule_area.height -= 1;
+        }
+        frame.render_stateful_widget_ref(&self.schedule, schedule_area, &mut self.schedule_state);
+
+        if let Some(command_line) = &self.command_line {
+            let line_area = Rect {
+                y: area.bottom().saturating_sub(1),
+                height: 1.min(area.height),
+                ..area
+            };
+            frame.render_widget_ref(command_line, line_area);
+        }
+
+        if let Some(details) = &self.details {
+            frame.render_widget_ref(details, area);
+        }
+
+        if let Some(queue) = &self.queue {
+            frame.render_widget_ref(queue, area);
+        }
+
+        if let Some(health) = &self.health {
+            frame.render_widget_ref(health, area);
+        }
+
+        if let Some(palette) = &self.palette {
+            frame.render_widget_ref(palette, area);
+        }
+
+        if !self.toasts.is_empty() {
+            frame.render_widget_ref(&self.toasts, area);
+        }
 
         /*
         let top_line = Text::from(format!(
@@ -263,3 +1736,35 @@ impl App {
         */
     }
 }
+
+// The schedules of all leagues, requested at the same time and kept in the order of `slugs`
+async fn get_schedules(
+    resources: &ResourceManager,
+    slugs: Vec<String>,
+    force: bool,
+) -> Vec<(String, Vec<models::Event>)> {
+    let schedules = join_all(slugs.iter().map(|slug| async move {
+        match force {
+            true => resources.fetch_schedule(slug).await,
+            false => resources.get_schedule(slug).await,
+        }
+    }))
+    .await;
+    slugs
+        .into_iter()
+        .zip(schedules)
+        .filter_map(|(slug, events)| Some((slug, events?)))
+        .collect()
+}
+
+fn format_countdown(duration: chrono::Duration) -> String {
+    let minutes = duration.num_minutes();
+    if minutes <= 0 {
+        return "starting".to_string();
+    }
+    match (minutes / (60 * 24), minutes / 60 % 24, minutes % 60) {
+        (0, 0, m) => format!("in {}m", m),
+        (0, h, m) => format!("in {}h {}m", h, m),
+        (d, h, _) => format!("in {}d {}h", d, h),
+    }
+}
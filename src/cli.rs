@@ -0,0 +1,222 @@
+use std::{net::IpAddr, path::PathBuf, time::Duration};
+
+use chrono::{DateTime, Local, LocalResult, NaiveDate, NaiveDateTime, TimeZone};
+use clap::{Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Config file to use instead of the default one
+    #[arg(short, long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Directory for the cache and the log file
+    #[arg(short, long, value_name = "PATH")]
+    pub data_dir: Option<PathBuf>,
+
+    /// Use the config, data and cache of a profile, kept apart from the others, Ex: spoiler-free
+    #[arg(short, long, value_name = "NAME", value_parser = parse_profile)]
+    pub profile: Option<String>,
+
+    /// Log filter, Ex: debug or lol_cal=trace
+    #[arg(short, long, value_name = "FILTER")]
+    pub log_level: Option<String>,
+
+    /// League to activate on startup instead of the default leagues, can be repeated
+    #[arg(short = 'L', long = "league", value_name = "NAME", global = true)]
+    pub leagues: Vec<String>,
+
+    /// Only use cached data, never fetching from the API
+    #[arg(long, conflicts_with = "refresh")]
+    pub offline: bool,
+
+    /// Never write to the cache directory, Ex: when it is read-only or shared
+    #[arg(long)]
+    pub read_only_cache: bool,
+
+    /// Read API responses from fixture files in DIR instead of the network, bypassing the cache
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["offline", "record"])]
+    pub fixtures: Option<PathBuf>,
+
+    /// Save API responses to DIR as fixtures, to replay later with --fixtures
+    #[arg(long, value_name = "DIR", conflicts_with = "offline")]
+    pub record: Option<PathBuf>,
+
+    /// Ignore cached leagues and schedules until they have been fetched again
+    #[arg(long)]
+    pub refresh: bool,
+
+    /// Print the schedule as plain text instead of starting the TUI, the default when stdout is
+    /// not a terminal
+    #[arg(long)]
+    pub plain: bool,
+
+    /// Never use colors, the same as the color = never setting
+    #[arg(long)]
+    pub no_color: bool,
+
+    /// Hide results and unplayed teams in every output, the same as turning off spoil_results
+    /// and spoil_matches
+    #[arg(long, global = true)]
+    pub no_spoilers: bool,
+
+    /// Pretend the clock started at TIME, in local time, to reproduce how the schedule looks
+    /// then, Ex: "2026-10-25 02:30"
+    #[arg(long, value_name = "TIME", value_parser = parse_time, global = true)]
+    pub fake_now: Option<DateTime<Local>>,
+
+    /// Write a commented default config file and exit
+    #[arg(long)]
+    pub init_config: bool,
+
+    /// Validate the config file strictly and exit
+    #[arg(long)]
+    pub check_config: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+// Subcommands print to stdout without starting the TUI
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print the next upcoming matches
+    Next {
+        /// Number of matches to print
+        #[arg(short = 'n', long, default_value_t = 5)]
+        count: usize,
+    },
+    /// Print the schedule as JSON
+    Json {
+        /// First date to include, as YYYY-MM-DD
+        #[arg(long, value_name = "DATE")]
+        from: Option<NaiveDate>,
+        /// Last date to include, as YYYY-MM-DD
+        #[arg(long, value_name = "DATE")]
+        to: Option<NaiveDate>,
+        /// Include results and unplayed teams regardless of the spoiler settings
+        #[arg(long)]
+        spoilers: bool,
+    },
+    /// Export the schedule as an iCalendar file
+    ExportIcs {
+        /// File to write, stdout if not given
+        #[arg(short, long, value_name = "PATH")]
+        out: Option<PathBuf>,
+        /// Include results and unplayed teams regardless of the spoiler settings
+        #[arg(long)]
+        spoilers: bool,
+    },
+    /// Print Markdown tables of the matches of the next seven days
+    Digest {
+        /// File to write, stdout if not given
+        #[arg(short, long, value_name = "PATH")]
+        out: Option<PathBuf>,
+        /// Include results and unplayed teams regardless of the spoiler settings
+        #[arg(long)]
+        spoilers: bool,
+    },
+    /// Send a command to a running instance through its socket, Ex: send league add LCK
+    Send {
+        /// Command in the command mode syntax
+        #[arg(required = true, trailing_var_arg = true)]
+        command: Vec<String>,
+    },
+    /// Keep the cache fresh and send notifications without a TUI
+    Watch {
+        /// How often to re-fetch the schedules, Ex: 5m, defaults to refresh_interval or 5m
+        #[arg(short, long, value_name = "DURATION", value_parser = parse_interval)]
+        interval: Option<Duration>,
+    },
+    /// Serve auto-refreshing iCalendar feeds over HTTP for calendar apps to subscribe to
+    Serve {
+        /// Address to listen on
+        #[arg(short, long, default_value = "127.0.0.1")]
+        bind: IpAddr,
+        /// Port to listen on
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+        /// How long schedules are served before they are fetched again, defaults to
+        /// refresh_interval or 30m
+        #[arg(short, long, value_name = "DURATION", value_parser = parse_interval)]
+        interval: Option<Duration>,
+        /// Include results and unplayed teams regardless of the spoiler settings
+        #[arg(long)]
+        spoilers: bool,
+    },
+}
+
+impl Command {
+    // Whether `--spoilers` was given
+    pub fn spoilers(&self) -> bool {
+        match self {
+            Command::Json { spoilers, .. }
+            | Command::ExportIcs { spoilers, .. }
+            | Command::Digest { spoilers, .. }
+            | Command::Serve { spoilers, .. } => *spoilers,
+            _ => false,
+        }
+    }
+}
+
+fn parse_interval(raw: &str) -> Result<Duration, String> {
+    match crate::config::parser::parse_duration(raw) {
+        Ok(Some(duration)) => Ok(duration),
+        Ok(None) => Err("the interval can not be none".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// Profiles are directory names, so they can't contain a path
+fn parse_profile(raw: &str) -> Result<String, String> {
+    match raw {
+        "" | "." | ".." => Err("expected a profile name".to_string()),
+        _ if raw.contains(['/', '\\']) => Err("a profile name can not contain a path".to_string()),
+        _ => Ok(raw.to_string()),
+    }
+}
+
+// Accepts RFC 3339 or a local "YYYY-MM-DD HH:MM", the earlier time of an hour repeated by a
+// DST change
+fn parse_time(raw: &str) -> Result<DateTime<Local>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(raw) {
+        return Ok(time.with_timezone(&Local));
+    }
+    let naive = NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M")
+        .or_else(|_| NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M"))
+        .map_err(|_| "expected YYYY-MM-DD HH:MM or an RFC 3339 time".to_string())?;
+    match Local.from_local_datetime(&naive) {
+        LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => Ok(time),
+        LocalResult::None => Err(format!("{} is skipped by a DST change", raw)),
+    }
+}
+
+impl Cli {
+    // Command line values take precedence over the config file and environment
+    pub fn apply(&self, config: &mut crate::config::Config) {
+        if let Some(data_dir) = &self.data_dir {
+            config.data_dir = data_dir.clone();
+        }
+        if let Some(log_level) = &self.log_level {
+            config.log_level = Some(log_level.clone());
+        }
+        if !self.leagues.is_empty() {
+            config.default_leagues = self.leagues.clone();
+        }
+        config.offline |= self.offline;
+        config.read_only_cache |= self.read_only_cache;
+        config.refresh = self.refresh;
+        if self.no_spoilers {
+            config.spoil_results = false;
+            config.spoil_matches = false;
+        }
+        if self.no_color {
+            config.color = crate::config::ColorMode::Never;
+        }
+        if let Some(dir) = &self.fixtures {
+            config.source = crate::net::Source::Replay(dir.clone());
+        } else if let Some(dir) = &self.record {
+            config.source = crate::net::Source::Record(dir.clone());
+        }
+    }
+}
@@ -0,0 +1,68 @@
+// The current time for everything shown or filtered by date. Faking it makes renders and
+// exports reproducible, or shows how the app behaves at another time, Ex: around midnight
+use std::sync::RwLock;
+
+use chrono::{DateTime, Local, TimeDelta};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fake {
+    Frozen(DateTime<Local>),
+    // Runs like the real clock, shifted by the offset
+    Shifted(TimeDelta),
+}
+
+impl Fake {
+    fn now(self) -> DateTime<Local> {
+        match self {
+            Fake::Frozen(time) => time,
+            Fake::Shifted(offset) => Local::now() + offset,
+        }
+    }
+}
+
+// Process wide, set from `--fake-now`
+static FAKE: RwLock<Option<Fake>> = RwLock::new(None);
+
+// A clock of its own for the app, the schedule and the notifier, so tests each run at their
+// own time. The default follows the process wide clock
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Clock(Option<Fake>);
+
+impl Clock {
+    pub fn frozen(time: DateTime<Local>) -> Self {
+        Self(Some(Fake::Frozen(time)))
+    }
+
+    // Keeps running from `time`
+    pub fn starting_at(time: DateTime<Local>) -> Self {
+        Self(Some(Fake::Shifted(time - Local::now())))
+    }
+
+    pub fn now(&self) -> DateTime<Local> {
+        match self.0 {
+            Some(fake) => fake.now(),
+            None => now(),
+        }
+    }
+}
+
+pub fn now() -> DateTime<Local> {
+    match *FAKE.read().unwrap_or_else(|e| e.into_inner()) {
+        Some(fake) => fake.now(),
+        None => Local::now(),
+    }
+}
+
+// `None` lets the real clock run again
+pub fn freeze(time: Option<DateTime<Local>>) {
+    set(time.map(Fake::Frozen));
+}
+
+// The clock keeps running from `time`
+pub fn start_at(time: DateTime<Local>) {
+    set(Some(Fake::Shifted(time - Local::now())));
+}
+
+fn set(fake: Option<Fake>) {
+    *FAKE.write().unwrap_or_else(|e| e.into_inner()) = fake;
+}
@@ -0,0 +1,278 @@
+use std::{path::PathBuf, time::Duration};
+
+use chrono::NaiveDate;
+
+use crate::{
+    config::{DateRange, keys::COMMANDS, parser::parse_duration},
+    event::AppEvent,
+};
+
+/// A command entered in command mode, Ex: `:goto 2025-05-12` or `:league add LCK`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Quit,
+    GotoToday,
+    Goto(NaiveDate),
+    League(LeagueAction, String),
+    Region(RegionAction, String),
+    Spoil(SpoilTarget, Toggle),
+    Range(DateRange),
+    // Reminds of the selected match this long before it starts, `None` removes the reminder
+    Remind(Option<Duration>),
+    // Writes the active schedule, or only favorite team matches, as `.ics`, `.json` or a
+    // `.md` digest of the next week. Safe exports hide results and unplayed teams whatever the
+    // schedule shows
+    Export {
+        path: PathBuf,
+        favorites: bool,
+        safe: bool,
+    },
+    // Writes the slugs of the selected leagues, one per line
+    ExportSelection(PathBuf),
+    // Selects the leagues listed in a file, in addition to or instead of the current ones
+    ImportSelection {
+        path: PathBuf,
+        replace: bool,
+    },
+    Event(AppEvent),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LeagueAction {
+    Add,
+    Remove,
+    Toggle,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegionAction {
+    Hide,
+    Show,
+    Toggle,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpoilTarget {
+    Results,
+    Matches,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Toggle {
+    On,
+    Off,
+    Toggle,
+}
+
+impl Toggle {
+    pub fn apply(self, value: bool) -> bool {
+        match self {
+            Toggle::On => true,
+            Toggle::Off => false,
+            Toggle::Toggle => !value,
+        }
+    }
+}
+
+pub const COMMAND_NAMES: &[&str] = &[
+    "quit",
+    "goto",
+    "league",
+    "region",
+    "group",
+    "spoil",
+    "range",
+    "remind",
+    "export",
+    "selection",
+];
+const LEAGUE_ACTIONS: &[&str] = &["add", "remove", "toggle"];
+const REGION_ACTIONS: &[&str] = &["hide", "show", "toggle"];
+const SELECTION_ACTIONS: &[&str] = &["export", "import"];
+const IMPORT_MODES: &[&str] = &["merge", "replace"];
+const SPOIL_TARGETS: &[&str] = &["results", "matches"];
+const TOGGLES: &[&str] = &["on", "off", "toggle"];
+
+pub fn parse(input: &str) -> Result<Command, String> {
+    let input = input.trim().trim_start_matches(':');
+    let (name, args) = input.split_once(' ').unwrap_or((input, ""));
+    let args = args.trim();
+
+    match name.to_lowercase().as_str() {
+        "" => Err("Empty command".to_string()),
+        "q" | "quit" => Ok(Command::Quit),
+        "goto" => match args.to_lowercase().as_str() {
+            "" | "today" => Ok(Command::GotoToday),
+            date => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map(Command::Goto)
+                .map_err(|_| format!("Invalid date '{}', expected YYYY-MM-DD", args)),
+        },
+        "league" => {
+            let (action, league) = args.split_once(' ').unwrap_or((args, ""));
+            let action = match action.to_lowercase().as_str() {
+                "add" => LeagueAction::Add,
+                "remove" => LeagueAction::Remove,
+                "toggle" => LeagueAction::Toggle,
+                _ => return Err(format!("Invalid league action '{}'", action)),
+            };
+            match league.trim() {
+                "" => Err("Missing league name".to_string()),
+                league => Ok(Command::League(action, league.to_string())),
+            }
+        }
+        "region" => {
+            let (action, region) = args.split_once(' ').unwrap_or((args, ""));
+            let action = match action.to_lowercase().as_str() {
+                "hide" => RegionAction::Hide,
+                "show" => RegionAction::Show,
+                "toggle" => RegionAction::Toggle,
+                _ => return Err(format!("Invalid region action '{}'", action)),
+            };
+            match region.trim() {
+                "" => Err("Missing region name".to_string()),
+                region => Ok(Command::Region(action, region.to_string())),
+            }
+        }
+        "spoil" => {
+            let mut target = SpoilTarget::Results;
+            let mut toggle = Toggle::Toggle;
+            for arg in args.split_whitespace() {
+                match arg.to_lowercase().as_str() {
+                    "results" => target = SpoilTarget::Results,
+                    "matches" => target = SpoilTarget::Matches,
+                    "on" | "yes" | "true" => toggle = Toggle::On,
+                    "off" | "no" | "false" => toggle = Toggle::Off,
+                    "toggle" => toggle = Toggle::Toggle,
+                    _ => return Err(format!("Invalid spoil argument '{}'", arg)),
+                }
+            }
+            Ok(Command::Spoil(target, toggle))
+        }
+        "range" => match args {
+            "" => Ok(Command::Event(AppEvent::CycleDateRange)),
+            range => DateRange::from_name(range)
+                .map(Command::Range)
+                .ok_or_else(|| format!("Invalid date range '{}'", range)),
+        },
+        "group" => match args {
+            "" => Err("Missing group name, Ex: :group majors".to_string()),
+            name => Ok(Command::Event(AppEvent::ActivateGroup(name.to_string()))),
+        },
+        "remind" => match args.to_lowercase().as_str() {
+            "" => Err("Missing lead time, Ex: :remind 15m".to_string()),
+            "off" => Ok(Command::Remind(None)),
+            before => parse_duration(before)
+                .map(Command::Remind)
+                .map_err(|_| format!("Invalid lead time '{}'", before)),
+        },
+        "export" => {
+            let (mut favorites, mut safe) = (false, false);
+            let mut path = args;
+            while let Some((first, rest)) = path.split_once(' ') {
+                match first.to_lowercase().as_str() {
+                    "favorites" => favorites = true,
+                    "safe" => safe = true,
+                    _ => break,
+                }
+                path = rest.trim_start();
+            }
+            let path = path.trim();
+            if path.is_empty() {
+                return Err("Missing export path, Ex: :export lck.ics".to_string());
+            }
+            let path = expand_path(path);
+            match path.extension().and_then(|e| e.to_str()) {
+                Some("ics" | "json" | "md") => Ok(Command::Export {
+                    path,
+                    favorites,
+                    safe,
+                }),
+                _ => Err("Export path has to end in .ics, .json or .md".to_string()),
+            }
+        }
+        "selection" => {
+            let (action, args) = args.split_once(' ').unwrap_or((args, ""));
+            let (replace, path) = match args.trim().split_once(' ') {
+                Some((mode, rest)) if mode.eq_ignore_ascii_case("replace") => (true, rest),
+                Some((mode, rest)) if mode.eq_ignore_ascii_case("merge") => (false, rest),
+                _ => (false, args),
+            };
+            let path = path.trim();
+            if path.is_empty() {
+                return Err("Missing selection path, Ex: :selection export leagues.txt".to_string());
+            }
+            match action.to_lowercase().as_str() {
+                "export" => Ok(Command::ExportSelection(expand_path(path))),
+                "import" => Ok(Command::ImportSelection {
+                    path: expand_path(path),
+                    replace,
+                }),
+                _ => Err(format!("Invalid selection action '{}'", action)),
+            }
+        }
+        _ => COMMANDS
+            .iter()
+            .find(|(command, _)| command.eq_ignore_ascii_case(name))
+            .map(|(_, event)| Command::Event(event.clone()))
+            .ok_or_else(|| format!("Unknown command '{}'", name)),
+    }
+}
+
+// Like a shell, expand a leading `~` to the home directory
+fn expand_path(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Completes the last word of `input`, returning the completed input if the
+/// word has any matching candidates.
+pub fn complete(input: &str) -> Option<String> {
+    let (head, word) = match input.rfind(' ') {
+        Some(i) => (&input[..=i], &input[i + 1..]),
+        None => ("", input),
+    };
+
+    let candidates: Vec<&str> = match head.split_whitespace().collect::<Vec<_>>().as_slice() {
+        [] => COMMAND_NAMES
+            .iter()
+            .copied()
+            .chain(COMMANDS.iter().map(|(name, _)| *name))
+            .collect(),
+        ["league"] => LEAGUE_ACTIONS.to_vec(),
+        ["region"] => REGION_ACTIONS.to_vec(),
+        ["spoil"] => SPOIL_TARGETS.iter().chain(TOGGLES).copied().collect(),
+        ["spoil", _] => TOGGLES.to_vec(),
+        ["goto"] => vec!["today"],
+        ["range"] => DateRange::ALL.iter().map(|r| r.name()).collect(),
+        ["remind"] => vec!["off"],
+        ["export"] => vec!["favorites", "safe"],
+        ["export", "favorites"] => vec!["safe"],
+        ["export", "safe"] => vec!["favorites"],
+        ["selection"] => SELECTION_ACTIONS.to_vec(),
+        ["selection", "import"] => IMPORT_MODES.to_vec(),
+        _ => Vec::new(),
+    };
+
+    let matches: Vec<&str> = candidates
+        .into_iter()
+        .filter(|c| c.to_lowercase().starts_with(&word.to_lowercase()))
+        .collect();
+
+    let first = matches.first()?;
+    let common = matches.iter().skip(1).fold(first.len(), |len, m| {
+        first
+            .chars()
+            .zip(m.chars())
+            .take(len)
+            .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+            .count()
+    });
+
+    if matches.len() == 1 {
+        Some(format!("{}{} ", head, first))
+    } else {
+        Some(format!("{}{}", head, &first[..common]))
+    }
+}
@@ -4,6 +4,9 @@ use core::fmt;
 pub enum Error {
     File(std::io::Error),
     NoConfigFile(String),
+    NoThemeFile(String),
+    NoLanguageFile(String),
+    ConfigFileExists(String),
     EmptyHeader(usize),
     IncompleteHeader(usize),
     EmptyKey(usize),
@@ -16,6 +19,7 @@ pub enum Error {
     InvalidBool(String),
     InvalidValue(String),
     UnknownKey(String, String),
+    UnknownSection(String),
 }
 
 impl fmt::Display for Error {
@@ -29,6 +33,23 @@ impl fmt::Display for Error {
                     path
                 )
             }
+            Error::NoThemeFile(theme) => {
+                write!(
+                    f,
+                    "Config parsing error: unable to find theme file for '{}'",
+                    theme
+                )
+            }
+            Error::NoLanguageFile(language) => {
+                write!(
+                    f,
+                    "Config parsing error: unable to find language file for '{}'",
+                    language
+                )
+            }
+            Error::ConfigFileExists(path) => {
+                write!(f, "Config error: config file already exists at {}", path)
+            }
             Error::EmptyHeader(line) => {
                 write!(
                     f,
@@ -113,6 +134,9 @@ impl fmt::Display for Error {
                     raw_key, subsection
                 )
             }
+            Error::UnknownSection(section) => {
+                write!(f, "Config parsing error: unknown section '{}'", section)
+            }
         }
     }
 }
@@ -122,6 +146,9 @@ impl std::error::Error for Error {
         match self {
             Error::File(e) => Some(e),
             Error::NoConfigFile(_) => None,
+            Error::NoThemeFile(_) => None,
+            Error::NoLanguageFile(_) => None,
+            Error::ConfigFileExists(_) => None,
             Error::EmptyHeader(_) => None,
             Error::IncompleteHeader(_) => None,
             Error::EmptyKey(_) => None,
@@ -134,6 +161,7 @@ impl std::error::Error for Error {
             Error::InvalidBool(_) => None,
             Error::InvalidValue(_) => None,
             Error::UnknownKey(_, _) => None,
+            Error::UnknownSection(_) => None,
         }
     }
 }
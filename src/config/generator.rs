@@ -0,0 +1,379 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::style::{Color, Style};
+use ratatui::widgets::BorderType;
+
+use super::error::Error;
+use super::parser::{SETTINGS, format_duration};
+use super::{keys::COMMANDS, style::MODIFIERS};
+use crate::config::Config;
+
+impl Config {
+    /// Writes a commented config file with every default value to `path`,
+    /// refusing to overwrite an existing file.
+    pub fn write_default<P: AsRef<Path>>(path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+        if path.exists() {
+            return Err(Error::ConfigFileExists(path.to_string_lossy().into_owned()));
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, default_config())?;
+        Ok(())
+    }
+}
+
+/// Generates the default config file, with every entry commented out.
+pub fn default_config() -> String {
+    let config = Config::default();
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# {} config file", env!("CARGO_PKG_NAME"));
+    let _ = writeln!(
+        out,
+        "# Generated from the default values, uncomment a line to change it"
+    );
+
+    section(&mut out, "settings");
+    for setting in SETTINGS {
+        entry(
+            &mut out,
+            setting.description,
+            setting.key,
+            &(setting.value)(&config),
+        );
+    }
+
+    section(&mut out, "favorites");
+    entry(
+        &mut out,
+        "a comma separated list of team codes to mark as favorites",
+        "teams",
+        "T1, G2, FLY",
+    );
+
+    section(&mut out, "teams");
+    entry(
+        &mut out,
+        "the code, and optionally name, shown instead of a team code from the API",
+        "KOI",
+        "MAD, MAD Lions",
+    );
+
+    section(&mut out, "groups");
+    entry(
+        &mut out,
+        "a name for a list of leagues, activated with :group NAME",
+        "majors",
+        "LCK, LPL, LEC, LTA North",
+    );
+
+    section(&mut out, "notifications");
+    let rule = &config.notifications.defaults;
+    entry(
+        &mut out,
+        "send desktop notifications about matches of active leagues",
+        "enabled",
+        &config.notifications.enabled.to_string(),
+    );
+    entry(
+        &mut out,
+        "notify when a refresh reschedules, adds or removes matches",
+        "schedule_changes",
+        &config.notifications.schedule_changes.to_string(),
+    );
+    entry(
+        &mut out,
+        "how long before the start of a match to send a reminder",
+        "before",
+        &format_duration(rule.before),
+    );
+    entry(
+        &mut out,
+        "a comma separated list of leagues to notify about, all when empty",
+        "leagues",
+        "LCK, LEC",
+    );
+    entry(
+        &mut out,
+        "a comma separated list of team codes to notify about, all when empty",
+        "teams",
+        "T1, G2",
+    );
+    entry(
+        &mut out,
+        "only notify about matches of favorite teams",
+        "favorites_only",
+        &rule.favorites_only.to_string(),
+    );
+    entry(
+        &mut out,
+        "notify when a match goes live",
+        "match_started",
+        &rule.match_started.to_string(),
+    );
+    entry(
+        &mut out,
+        "notify when a match finishes, without the score",
+        "match_finished",
+        &rule.match_finished.to_string(),
+    );
+    entry(
+        &mut out,
+        "local times during which no notifications are sent",
+        "quiet_hours",
+        "23:00-08:00",
+    );
+    let _ = writeln!(
+        out,
+        "# Sections named [notifications.NAME] add rules that start out from these values"
+    );
+
+    section(&mut out, "hooks");
+    let _ = writeln!(
+        out,
+        "# Shell commands run with LOL_CAL_LEAGUE, LOL_CAL_TEAM1, LOL_CAL_TEAM2, LOL_CAL_START_TIME,"
+    );
+    let _ = writeln!(
+        out,
+        "# LOL_CAL_STREAM_URL and more describing the match, for matches the notification rules match"
+    );
+    entry(
+        &mut out,
+        "run when a match is due to start within the reminder time",
+        "on_reminder",
+        "notify-send \"$LOL_CAL_LEAGUE\" \"$LOL_CAL_TEAM1 vs $LOL_CAL_TEAM2\"",
+    );
+    entry(
+        &mut out,
+        "run when a match goes live",
+        "on_match_start",
+        "xdg-open \"$LOL_CAL_STREAM_URL\"",
+    );
+    entry(
+        &mut out,
+        "run when a match finishes",
+        "on_match_end",
+        "echo \"$LOL_CAL_MATCH_ID\" >> ~/watched",
+    );
+
+    section(&mut out, "keybindings");
+    let _ = writeln!(
+        out,
+        "# Available commands: {}",
+        COMMANDS
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    for (name, command) in COMMANDS {
+        let mut keys: Vec<String> = config
+            .keybindings
+            .iter()
+            .filter(|(_, c)| *c == command)
+            .map(|(key, _)| format_key_event(key))
+            .collect();
+        keys.sort();
+        for key in keys {
+            let _ = writeln!(out, "# {} = {}", key, name);
+        }
+    }
+
+    let styles = &config.style;
+    section(&mut out, "style");
+    entry(
+        &mut out,
+        "one of: plain, rounded, double, thick, none",
+        "border",
+        format_border_type(styles.border),
+    );
+    entry(
+        &mut out,
+        "border of the leagues pane, defaults to border",
+        "border.leagues",
+        format_border_type(styles.leagues_border()),
+    );
+    entry(
+        &mut out,
+        "border of the schedule pane, defaults to border",
+        "border.schedule",
+        format_border_type(styles.schedule_border()),
+    );
+    entry(
+        &mut out,
+        "border of popups, which always have one, defaults to border",
+        "border.popup",
+        format_border_type(Some(styles.popup_border())),
+    );
+    let style_entries = [
+        ("default foreground color", "default", Some(styles.default)),
+        ("highlight color", "highlight", Some(styles.highlight)),
+        ("selected color", "selected", Some(styles.selected)),
+        ("winner color", "winner", styles.winner),
+        ("loser color", "loser", styles.loser),
+        ("date header color", "date", Some(styles.date())),
+        ("pane title color", "title", Some(styles.title())),
+        ("shown/total counts color", "counts", Some(styles.counts())),
+        ("day separator color", "separator", Some(styles.separator())),
+        ("match state color", "state", styles.state),
+        (
+            "in progress match state color",
+            "in_progress",
+            styles.in_progress,
+        ),
+        (
+            "game score of matches in progress color",
+            "live_score",
+            styles.live_score,
+        ),
+        ("block and league color", "info", styles.info),
+        ("favorite team color", "favorite", styles.favorite),
+        ("series progress color", "series", styles.series),
+        ("conflicting match time color", "conflict", styles.conflict),
+    ];
+    for (description, key, style) in style_entries {
+        entry(&mut out, description, key, &format_optional_style(style));
+    }
+
+    out
+}
+
+fn section(out: &mut String, name: &str) {
+    let _ = writeln!(out, "\n[{}]", name);
+}
+
+// Unset values are left empty, to be filled in when uncommenting the line
+fn entry(out: &mut String, description: &str, key: &str, value: &str) {
+    let _ = writeln!(out, "# {}", description);
+    let _ = writeln!(out, "{}", format!("# {} = {}", key, value).trim_end());
+}
+
+fn format_key_event(key: &KeyEvent) -> String {
+    let mut out = String::new();
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        out.push_str("Ctrl-");
+    }
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        out.push_str("Alt-");
+    }
+    if key.modifiers.contains(KeyModifiers::SHIFT) && key.code != KeyCode::BackTab {
+        out.push_str("Shift-");
+    }
+    let code = match key.code {
+        KeyCode::Esc => "esc".to_string(),
+        KeyCode::Enter => "enter".to_string(),
+        KeyCode::Left => "left".to_string(),
+        KeyCode::Right => "right".to_string(),
+        KeyCode::Up => "up".to_string(),
+        KeyCode::Down => "down".to_string(),
+        KeyCode::Home => "home".to_string(),
+        KeyCode::End => "end".to_string(),
+        KeyCode::PageUp => "pageup".to_string(),
+        KeyCode::PageDown => "pagedown".to_string(),
+        KeyCode::BackTab => "backtab".to_string(),
+        KeyCode::Backspace => "backspace".to_string(),
+        KeyCode::Delete => "delete".to_string(),
+        KeyCode::Insert => "insert".to_string(),
+        KeyCode::Tab => "tab".to_string(),
+        KeyCode::F(n) => format!("f{}", n),
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Char('-') => "minus".to_string(),
+        KeyCode::Char(':') => "colon".to_string(),
+        KeyCode::Char(c) => c.to_ascii_lowercase().to_string(),
+        _ => "unknown".to_string(),
+    };
+    out.push_str(&code);
+    out
+}
+
+fn format_border_type(border: Option<BorderType>) -> &'static str {
+    match border {
+        Some(BorderType::Plain) => "plain",
+        Some(BorderType::Rounded) => "rounded",
+        Some(BorderType::Double) => "double",
+        Some(BorderType::Thick) => "thick",
+        _ => "none",
+    }
+}
+
+fn format_optional_style(style: Option<Style>) -> String {
+    match style {
+        Some(style) => format_style(style),
+        None => "none".to_string(),
+    }
+}
+
+fn format_style(style: Style) -> String {
+    let mut parts: Vec<String> = MODIFIERS
+        .iter()
+        .filter(|(_, modifier)| style.add_modifier.contains(*modifier))
+        .map(|(name, _)| name.to_string())
+        .collect();
+    parts.push(format_color(style.fg.unwrap_or(Color::Reset)));
+    if let Some(bg) = style.bg {
+        parts.push("on".to_string());
+        parts.push(format_color(bg));
+    }
+    parts.join(" ")
+}
+
+fn format_color(color: Color) -> String {
+    let name = match color {
+        Color::Black | Color::Indexed(0) => "black",
+        Color::Red | Color::Indexed(1) => "red",
+        Color::Green | Color::Indexed(2) => "green",
+        Color::Yellow | Color::Indexed(3) => "yellow",
+        Color::Blue | Color::Indexed(4) => "blue",
+        Color::Magenta | Color::Indexed(5) => "magenta",
+        Color::Cyan | Color::Indexed(6) => "cyan",
+        Color::Gray | Color::Indexed(7) => "gray",
+        Color::DarkGray | Color::Indexed(8) => "bright black",
+        Color::LightRed | Color::Indexed(9) => "bright red",
+        Color::LightGreen | Color::Indexed(10) => "bright green",
+        Color::LightYellow | Color::Indexed(11) => "bright yellow",
+        Color::LightBlue | Color::Indexed(12) => "bright blue",
+        Color::LightMagenta | Color::Indexed(13) => "bright magenta",
+        Color::LightCyan | Color::Indexed(14) => "bright cyan",
+        Color::White | Color::Indexed(15) => "white",
+        Color::Rgb(r, g, b) => return format!("#{:02x}{:02x}{:02x}", r, g, b),
+        Color::Indexed(i) => return format!("color{}", i),
+        _ => "reset",
+    };
+    name.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_every_setting() {
+        let config = default_config();
+        let settings =
+            &config[config.find("[settings]").unwrap()..config.find("[favorites]").unwrap()];
+        for setting in SETTINGS {
+            let line = format!("\n# {} =", setting.key);
+            assert!(settings.contains(&line), "'{}' is missing", setting.key);
+        }
+    }
+
+    #[test]
+    fn writes_values_that_parse_back_to_the_defaults() {
+        let defaults = Config::default();
+        for setting in SETTINGS {
+            let value = (setting.value)(&defaults);
+            if value.is_empty() {
+                continue;
+            }
+            let mut config = Config::default();
+            (setting.parse)(&mut config, &value)
+                .unwrap_or_else(|e| panic!("'{} = {}' does not parse: {}", setting.key, value, e));
+            assert_eq!((setting.value)(&config), value, "'{}' changed", setting.key);
+        }
+    }
+}
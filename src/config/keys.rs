@@ -0,0 +1,219 @@
+// Keybindings and the commands they run, only parsed with the TUI
+use std::{
+    collections::HashMap,
+    ops::{Deref, DerefMut},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::{Config, Error};
+use crate::event::AppEvent;
+
+#[derive(Debug)]
+pub struct KeyBindings(pub HashMap<KeyEvent, AppEvent>);
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let mut map = HashMap::new();
+
+        macro_rules! bind {
+            ($code:expr => $event:expr) => {
+                map.insert(KeyEvent::new($code, KeyModifiers::NONE), $event);
+            };
+            ($code:expr, $mods:expr => $event:expr) => {
+                map.insert(KeyEvent::new($code, $mods), $event);
+            };
+        }
+
+        bind!(KeyCode::Char('q') => AppEvent::Quit);
+        bind!(KeyCode::Char('c'), KeyModifiers::CONTROL => AppEvent::Quit);
+
+        bind!(KeyCode::Char('k') => AppEvent::Up);
+        bind!(KeyCode::Char('j') => AppEvent::Down);
+        bind!(KeyCode::Char('h') => AppEvent::Left);
+        bind!(KeyCode::Char('l') => AppEvent::Right);
+
+        bind!(KeyCode::Up => AppEvent::Up);
+        bind!(KeyCode::Down => AppEvent::Down);
+        bind!(KeyCode::Left => AppEvent::Left);
+        bind!(KeyCode::Right => AppEvent::Right);
+        bind!(KeyCode::Char(' ') => AppEvent::Select);
+        bind!(KeyCode::Char('u') => AppEvent::Undo);
+        bind!(KeyCode::Tab => AppEvent::FocusNext);
+        bind!(KeyCode::BackTab, KeyModifiers::SHIFT => AppEvent::FocusPrev);
+
+        bind!(KeyCode::Char('g'), KeyModifiers::CONTROL => AppEvent::GotoToday);
+        bind!(KeyCode::Char('f') => AppEvent::GotoFavorite);
+        bind!(KeyCode::Char('n') => AppEvent::GotoLive);
+        bind!(KeyCode::Char('d') => AppEvent::CycleDateRange);
+        bind!(KeyCode::Char('l'), KeyModifiers::SHIFT => AppEvent::CycleLeagueSort);
+        bind!(KeyCode::Char('u'), KeyModifiers::SHIFT => AppEvent::ToggleUpcomingLeagues);
+        bind!(KeyCode::Char('z') => AppEvent::ToggleFold);
+        bind!(KeyCode::Char('i') => AppEvent::ShowDetails);
+        bind!(KeyCode::Char('v') => AppEvent::ShowQueue);
+        bind!(KeyCode::Char('n'), KeyModifiers::SHIFT => AppEvent::ShowHealth);
+        bind!(KeyCode::Char('w'), KeyModifiers::SHIFT => AppEvent::ToggleWatched);
+        bind!(KeyCode::Char('r'), KeyModifiers::SHIFT => AppEvent::RemindMatch);
+        bind!(KeyCode::Char('o') => AppEvent::OpenLink);
+        bind!(KeyCode::Char('w') => AppEvent::WatchStream);
+        bind!(KeyCode::Char('s'), KeyModifiers::CONTROL => AppEvent::ToggleSpoilResults);
+        bind!(KeyCode::Char('s'), KeyModifiers::SHIFT => AppEvent::ToggleSpoilMatches);
+
+        bind!(KeyCode::Char('r') => AppEvent::ReloadSchedule);
+        // Some terminals report ':' with the shift modifier
+        bind!(KeyCode::Char(':') => AppEvent::CommandMode);
+        bind!(KeyCode::Char(':'), KeyModifiers::SHIFT => AppEvent::CommandMode);
+        bind!(KeyCode::Char('p'), KeyModifiers::CONTROL => AppEvent::CommandPalette);
+        bind!(KeyCode::Char('m') => AppEvent::ToggleMouse);
+        bind!(KeyCode::Char('z'), KeyModifiers::CONTROL => AppEvent::Suspend);
+
+        KeyBindings(map)
+    }
+}
+
+impl Deref for KeyBindings {
+    type Target = HashMap<KeyEvent, AppEvent>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for KeyBindings {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+pub(super) fn parse_key_event(raw: &str) -> Result<KeyEvent, Error> {
+    let raw_lower = raw.to_ascii_lowercase();
+    let (remaining, modifiers) = extract_modifiers(&raw_lower);
+    parse_key_code_with_modifiers(remaining, modifiers)
+}
+
+// Every command that can be bound to a key, by its name in the config file
+pub const COMMANDS: &[(&str, AppEvent)] = &[
+    ("Quit", AppEvent::Quit),
+    ("Up", AppEvent::Up),
+    ("Down", AppEvent::Down),
+    ("Left", AppEvent::Left),
+    ("Right", AppEvent::Right),
+    ("Select", AppEvent::Select),
+    ("Undo", AppEvent::Undo),
+    ("FocusNext", AppEvent::FocusNext),
+    ("FocusPrev", AppEvent::FocusPrev),
+    ("GotoToday", AppEvent::GotoToday),
+    ("GotoFavorite", AppEvent::GotoFavorite),
+    ("GotoLive", AppEvent::GotoLive),
+    ("CycleDateRange", AppEvent::CycleDateRange),
+    ("CycleLeagueSort", AppEvent::CycleLeagueSort),
+    ("ToggleUpcomingLeagues", AppEvent::ToggleUpcomingLeagues),
+    ("ToggleFold", AppEvent::ToggleFold),
+    ("RemindMatch", AppEvent::RemindMatch),
+    ("ShowDetails", AppEvent::ShowDetails),
+    ("ShowQueue", AppEvent::ShowQueue),
+    ("ShowHealth", AppEvent::ShowHealth),
+    ("ToggleWatched", AppEvent::ToggleWatched),
+    ("OpenLink", AppEvent::OpenLink),
+    ("WatchStream", AppEvent::WatchStream),
+    ("ToggleSpoilResults", AppEvent::ToggleSpoilResults),
+    ("ToggleSpoilMatches", AppEvent::ToggleSpoilMatches),
+    ("ReloadLeagues", AppEvent::ReloadLeagues),
+    ("ReloadSchedule", AppEvent::ReloadSchedule),
+    ("RefreshSchedule", AppEvent::RefreshSchedule),
+    ("CommandMode", AppEvent::CommandMode),
+    ("CommandPalette", AppEvent::CommandPalette),
+    ("ToggleMouse", AppEvent::ToggleMouse),
+    ("Suspend", AppEvent::Suspend),
+];
+
+// Besides the commands, `Group NAME` activates a league group
+pub(super) fn parse_command(config: &Config, raw: &str) -> Result<AppEvent, Error> {
+    if let Some(name) = raw.strip_prefix("Group ") {
+        return config
+            .league_group(name.trim())
+            .map(|(name, _)| AppEvent::ActivateGroup(name.to_string()))
+            .ok_or_else(|| Error::InvalidCommand(raw.to_string()));
+    }
+    COMMANDS
+        .iter()
+        .find(|(name, _)| *name == raw)
+        .map(|(_, command)| command.clone())
+        .ok_or_else(|| Error::InvalidCommand(raw.to_string()))
+}
+
+fn extract_modifiers(raw: &str) -> (&str, KeyModifiers) {
+    let mut modifiers = KeyModifiers::empty();
+    let mut current = raw;
+
+    loop {
+        match current {
+            rest if rest.starts_with("ctrl-") => {
+                modifiers.insert(KeyModifiers::CONTROL);
+                current = &rest[5..];
+            }
+            rest if rest.starts_with("alt-") => {
+                modifiers.insert(KeyModifiers::ALT);
+                current = &rest[4..];
+            }
+            rest if rest.starts_with("shift-") => {
+                modifiers.insert(KeyModifiers::SHIFT);
+                current = &rest[6..];
+            }
+            _ => break, // break out of the loop if no known prefix is detected
+        };
+    }
+
+    (current, modifiers)
+}
+
+fn parse_key_code_with_modifiers(
+    raw: &str,
+    mut modifiers: KeyModifiers,
+) -> Result<KeyEvent, Error> {
+    let c = match raw {
+        "esc" => KeyCode::Esc,
+        "enter" => KeyCode::Enter,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "backtab" => {
+            modifiers.insert(KeyModifiers::SHIFT);
+            KeyCode::BackTab
+        }
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "f1" => KeyCode::F(1),
+        "f2" => KeyCode::F(2),
+        "f3" => KeyCode::F(3),
+        "f4" => KeyCode::F(4),
+        "f5" => KeyCode::F(5),
+        "f6" => KeyCode::F(6),
+        "f7" => KeyCode::F(7),
+        "f8" => KeyCode::F(8),
+        "f9" => KeyCode::F(9),
+        "f10" => KeyCode::F(10),
+        "f11" => KeyCode::F(11),
+        "f12" => KeyCode::F(12),
+        "space" => KeyCode::Char(' '),
+        "hyphen" => KeyCode::Char('-'),
+        "minus" => KeyCode::Char('-'),
+        "tab" => KeyCode::Tab,
+        "colon" => KeyCode::Char(':'),
+        c if c.len() == 1 => {
+            let mut c = c.chars().next().unwrap();
+            if modifiers.contains(KeyModifiers::SHIFT) {
+                c = c.to_ascii_uppercase();
+            }
+            KeyCode::Char(c)
+        }
+        _ => return Err(Error::InvalidKeybind(raw.to_string())),
+    };
+    Ok(KeyEvent::new(c, modifiers))
+}
@@ -1,130 +1,455 @@
-use std::{
-    collections::HashMap,
-    ops::{Deref, DerefMut},
-    path::PathBuf,
-};
+// Parsing the config file builds without the TUI, the keybindings and styles it has no use
+// for are behind the `tui` feature
+use std::{collections::HashMap, path::PathBuf, time::Duration};
 
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use chrono::{Datelike, Days, NaiveDate, NaiveTime, Weekday};
 use lazy_static::lazy_static;
-use ratatui::{
-    style::{Style, Stylize},
-    symbols::line,
-    widgets::BorderType,
-};
 use utils::{get_config_dir, get_data_dir};
 
-use crate::event::AppEvent;
+use crate::models::{Event, StratType, Strategy};
 
 mod error;
 pub use error::Error;
+#[cfg(feature = "tui")]
+pub mod generator;
+#[cfg(feature = "tui")]
+pub mod keys;
 pub mod parser;
+pub mod strings;
+#[cfg(feature = "tui")]
+pub mod style;
 pub mod utils;
 
+#[cfg(feature = "tui")]
+pub use keys::KeyBindings;
+#[cfg(feature = "tui")]
+pub use style::Styles;
+
 lazy_static! {
     pub static ref PROJECT_NAME: String = env!("CARGO_CRATE_NAME").to_uppercase().to_string();
 }
 
-#[derive(Debug)]
-pub struct KeyBindings(pub HashMap<KeyEvent, AppEvent>);
+// What of an unplayed match is hidden when `spoil_matches` is off, each scope hides what the
+// previous ones do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MaskScope {
+    Teams,
+    // The teams and the start time
+    Time,
+    // Everything but the league, Ex: the block name and format can tell a final apart
+    Row,
+}
 
-impl Default for KeyBindings {
-    fn default() -> Self {
-        let mut map = HashMap::new();
-
-        macro_rules! bind {
-            ($code:expr => $event:expr) => {
-                map.insert(KeyEvent::new($code, KeyModifiers::NONE), $event);
-            };
-            ($code:expr, $mods:expr => $event:expr) => {
-                map.insert(KeyEvent::new($code, $mods), $event);
-            };
+// Which days of the schedule are shown, cycled through with a keybinding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateRange {
+    All,
+    // Today and later
+    Upcoming,
+    Today,
+    // The 7 days starting today
+    Week,
+    // The coming Saturday and Sunday, or the current ones during a weekend
+    Weekend,
+}
+
+impl DateRange {
+    pub const ALL: [DateRange; 5] = [
+        DateRange::All,
+        DateRange::Upcoming,
+        DateRange::Today,
+        DateRange::Week,
+        DateRange::Weekend,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DateRange::All => "all",
+            DateRange::Upcoming => "upcoming",
+            DateRange::Today => "today",
+            DateRange::Week => "week",
+            DateRange::Weekend => "weekend",
         }
+    }
 
-        bind!(KeyCode::Char('q') => AppEvent::Quit);
-        bind!(KeyCode::Char('c'), KeyModifiers::CONTROL => AppEvent::Quit);
+    pub fn from_name(name: &str) -> Option<DateRange> {
+        Self::ALL
+            .into_iter()
+            .find(|range| range.name().eq_ignore_ascii_case(name))
+    }
 
-        bind!(KeyCode::Char('k') => AppEvent::Up);
-        bind!(KeyCode::Char('j') => AppEvent::Down);
-        bind!(KeyCode::Char('h') => AppEvent::Left);
-        bind!(KeyCode::Char('l') => AppEvent::Right);
+    pub fn next(self) -> DateRange {
+        let i = Self::ALL
+            .iter()
+            .position(|r| *r == self)
+            .unwrap_or_default();
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
 
-        bind!(KeyCode::Up => AppEvent::Up);
-        bind!(KeyCode::Down => AppEvent::Down);
-        bind!(KeyCode::Left => AppEvent::Left);
-        bind!(KeyCode::Right => AppEvent::Right);
-        bind!(KeyCode::Char(' ') => AppEvent::Select);
+    // First and last day of the range, both inclusive
+    pub fn bounds(self, today: NaiveDate) -> (Option<NaiveDate>, Option<NaiveDate>) {
+        match self {
+            DateRange::All => (None, None),
+            DateRange::Upcoming => (Some(today), None),
+            DateRange::Today => (Some(today), Some(today)),
+            DateRange::Week => (Some(today), Some(today + Days::new(6))),
+            DateRange::Weekend => {
+                let saturday = match today.weekday() {
+                    Weekday::Sun => today - Days::new(1),
+                    day => today + Days::new(5 - day.num_days_from_monday() as u64),
+                };
+                (Some(saturday), Some(saturday + Days::new(1)))
+            }
+        }
+    }
+}
 
-        bind!(KeyCode::Char('g'), KeyModifiers::CONTROL => AppEvent::GotoToday);
-        bind!(KeyCode::Char('s'), KeyModifiers::CONTROL => AppEvent::ToggleSpoilResults);
-        bind!(KeyCode::Char('s'), KeyModifiers::SHIFT => AppEvent::ToggleSpoilMatches);
+// Order of the leagues pane, cycled through with a keybinding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeagueSort {
+    // As ordered on lolesports
+    Priority,
+    Name,
+    // Grouped by region, by priority within a region
+    Region,
+}
 
-        bind!(KeyCode::Char('r') => AppEvent::ReloadSchedule);
+impl LeagueSort {
+    pub const ALL: [LeagueSort; 3] = [LeagueSort::Priority, LeagueSort::Name, LeagueSort::Region];
 
-        KeyBindings(map)
+    pub fn name(self) -> &'static str {
+        match self {
+            LeagueSort::Priority => "priority",
+            LeagueSort::Name => "name",
+            LeagueSort::Region => "region",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<LeagueSort> {
+        Self::ALL
+            .into_iter()
+            .find(|sort| sort.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn next(self) -> LeagueSort {
+        let i = Self::ALL
+            .iter()
+            .position(|s| *s == self)
+            .unwrap_or_default();
+        Self::ALL[(i + 1) % Self::ALL.len()]
     }
 }
 
-impl Deref for KeyBindings {
-    type Target = HashMap<KeyEvent, AppEvent>;
+// How the state column of the schedule shows the state of a match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateFormat {
+    Text,
+    // Only the glyph, leaving more room for team names
+    Glyph,
+    Both,
+}
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    Never,
+    // Colors unless the NO_COLOR environment variable is set
+    Auto,
+    Always,
+}
+
+// Which matches a notification rule applies to, and what it notifies about
+#[derive(Debug, Clone)]
+pub struct NotificationRule {
+    pub name: String,
+    // How long before a match starts to send a reminder, `None` sends none
+    pub before: Option<Duration>,
+    // League names or slugs, empty matches every active league
+    pub leagues: Vec<String>,
+    // Team codes, empty matches every team
+    pub teams: Vec<String>,
+    pub favorites_only: bool,
+    pub match_started: bool,
+    pub match_finished: bool,
+    // Local start and end time, may wrap past midnight
+    pub quiet_hours: Option<(NaiveTime, NaiveTime)>,
+}
+
+impl Default for NotificationRule {
+    fn default() -> Self {
+        NotificationRule {
+            name: "notifications".to_string(),
+            before: Some(Duration::from_secs(10 * 60)),
+            leagues: Vec::new(),
+            teams: Vec::new(),
+            favorites_only: false,
+            match_started: false,
+            match_finished: false,
+            quiet_hours: None,
+        }
     }
 }
 
-impl DerefMut for KeyBindings {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+impl NotificationRule {
+    pub fn is_quiet(&self, time: NaiveTime) -> bool {
+        match self.quiet_hours {
+            Some((start, end)) if start <= end => start <= time && time < end,
+            Some((start, end)) => start <= time || time < end,
+            None => false,
+        }
     }
 }
 
-#[derive(Debug)]
-pub struct Styles {
-    pub border: Option<BorderType>,
-    pub border_set: Option<line::Set>,
-    pub default: Style,
-    pub highlight: Style,
-    pub selected: Style,
-    pub winner: Option<Style>,
-    pub loser: Option<Style>,
+#[derive(Debug, Default)]
+pub struct Notifications {
+    pub enabled: bool,
+    // Also notify when a refresh reschedules, adds or removes matches
+    pub schedule_changes: bool,
+    // The `[notifications]` section, which named rules start out from
+    pub defaults: NotificationRule,
+    // Rules from `[notifications.NAME]` sections
+    pub rules: Vec<NotificationRule>,
 }
 
-impl Default for Styles {
-    fn default() -> Self {
-        Styles {
-            border: Some(BorderType::Plain),
-            border_set: Some(line::NORMAL),
-            default: Style::default(),
-            highlight: Style::default().blue(),
-            selected: Style::default().red().bold(),
-            winner: Some(Style::default().green()),
-            loser: None,
+impl Notifications {
+    // Without named rules the `[notifications]` section is the only rule
+    pub fn rules(&self) -> &[NotificationRule] {
+        if self.rules.is_empty() {
+            std::slice::from_ref(&self.defaults)
+        } else {
+            &self.rules
+        }
+    }
+}
+
+// Shell commands run when a match is notified about, with the match in the environment
+#[derive(Debug, Default)]
+pub struct Hooks {
+    pub on_match_start: Option<String>,
+    pub on_match_end: Option<String>,
+    pub on_reminder: Option<String>,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        self.on_match_start.is_none() && self.on_match_end.is_none() && self.on_reminder.is_none()
+    }
+}
+
+// The code and optionally the name shown for a team instead of the ones from the API
+#[derive(Debug, Clone)]
+pub struct TeamOverride {
+    pub short: String,
+    pub name: Option<String>,
+}
+
+impl TeamOverride {
+    // Applies the `[teams]` section, favorites and rules then match the new codes
+    pub fn apply(overrides: &HashMap<String, TeamOverride>, events: &mut [Event]) {
+        for team in events.iter_mut().flat_map(|e| e.teams.iter_mut()) {
+            if let Some(team_override) = overrides.get(&team.short.to_uppercase()) {
+                team.short = team_override.short.to_string();
+                if let Some(name) = &team_override.name {
+                    team.name = name.to_string();
+                }
+            }
         }
     }
 }
 
 #[derive(Debug)]
 pub struct Config {
+    pub strict: bool,
+    pub warnings: Vec<String>,
     pub config_dir: PathBuf,
     pub data_dir: PathBuf,
+    pub log_level: Option<String>,
+    pub log_file: Option<PathBuf>,
+    // Unix socket accepting commands, relative paths are in the data directory
+    pub socket: Option<PathBuf>,
     pub default_leagues: Vec<String>,
     pub spoil_results: bool,
     pub spoil_matches: bool,
+    // Shown instead of hidden teams
+    pub spoiler_mask: String,
+    pub spoiler_mask_scope: MaskScope,
+    pub date_range: DateRange,
+    pub league_sort: LeagueSort,
+    pub state_format: StateFormat,
+    // Glyphs of completed, in progress and unstarted matches
+    pub state_glyphs: [String; 3],
+    // Shown next to the winner of a completed match when results are spoiled
+    pub winner_glyph: Option<String>,
+    // Active matches starting at most this far apart are marked as conflicting
+    pub conflict_window: Option<Duration>,
+    // Estimated lengths of a Bo1, Bo3 and Bo5 series
+    pub series_durations: [Option<Duration>; 3],
     pub automatic_reload: bool,
+    pub offline: bool,
+    // Never write to the cache directory, keeping fetched data in memory for the session
+    pub read_only_cache: bool,
+    // Ignore cached data until it is fetched again, only set from the command line
+    pub refresh: bool,
+    // Replay or record API responses as fixtures, only set from the command line
+    pub source: crate::net::Source,
+    pub tick_rate: Option<Duration>,
+    // Redraws are coalesced to at most this many frames a second, none is unlimited
+    pub max_fps: Option<u32>,
+    pub refresh_interval: Option<Duration>,
+    pub live_refresh_interval: Option<Duration>,
+    // Most API requests sent in a minute, the rest wait, none is unlimited
+    pub request_limit: Option<usize>,
+    // Skips verifying the certificates of the API, for proxies that intercept TLS
+    pub danger_accept_invalid_certs: bool,
+    // PEM certificates trusted besides the system ones
+    pub ca_bundle: Option<PathBuf>,
+    pub mouse: bool,
+    // Unbound letters typed in the leagues pane jump to a league
+    pub type_ahead: bool,
+    pub stream_locale: String,
+    // Command template for WatchStream, `{url}` is replaced with the stream
+    pub player: String,
+    pub player_terminal: bool,
+    pub hyperlinks: bool,
+    pub strings: strings::Strings,
+    pub color: ColorMode,
+    // Whether the terminal shows RGB colors, otherwise they are mapped to the 16 basic colors
+    pub truecolor: bool,
+    pub terminal_title: bool,
+    // A line above the schedule with the matches of today
+    pub summary: bool,
+    // The line above the first day of a month names the month and year
+    pub month_headers: bool,
+    // A line above the schedule with the matches in progress
+    pub live_banner: bool,
+    // The schedule header counts the matches of each active league
+    pub league_counts: bool,
+    // Hide leagues without a match yet to start in their cached schedule
+    pub upcoming_leagues_only: bool,
+    // How many recently activated leagues are listed above the others
+    pub recent_leagues: usize,
+    pub scroll_off: Option<usize>,
+    // Empty columns kept on both sides inside the panes
+    pub padding: u16,
+    // Empty columns between the columns of a match
+    pub column_spacing: u16,
+    pub hidden_regions: Vec<String>,
+    pub favorite_teams: Vec<String>,
+    // Upper case API team codes and what to show instead
+    pub team_overrides: HashMap<String, TeamOverride>,
+    // Named lists of leagues that replace the selection at once, in the order of the file
+    pub league_groups: Vec<(String, Vec<String>)>,
+    pub notifications: Notifications,
+    pub hooks: Hooks,
+    #[cfg(feature = "tui")]
     pub keybindings: KeyBindings,
+    #[cfg(feature = "tui")]
     pub style: Styles,
 }
 
+impl Config {
+    pub fn socket_path(&self) -> Option<PathBuf> {
+        self.socket.as_ref().map(|path| self.data_dir.join(path))
+    }
+
+    // Whether output to a terminal, or elsewhere if `terminal` is false, is colored
+    pub fn use_color(&self, terminal: bool) -> bool {
+        match self.color {
+            ColorMode::Never => false,
+            ColorMode::Always => true,
+            ColorMode::Auto => {
+                terminal && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+            }
+        }
+    }
+
+    // Estimated length of a series, other formats count as that many Bo1 games
+    pub fn series_duration(&self, strategy: &Strategy) -> Option<Duration> {
+        match (&strategy.strat_type, strategy.count) {
+            (StratType::BestOf(_), 3) => self.series_durations[1],
+            (StratType::BestOf(_), 5) => self.series_durations[2],
+            (_, count) => self.series_durations[0].and_then(|d| d.checked_mul(count as u32)),
+        }
+    }
+
+    pub fn league_group(&self, name: &str) -> Option<&(String, Vec<String>)> {
+        self.league_groups
+            .iter()
+            .find(|(group, _)| group.eq_ignore_ascii_case(name))
+    }
+
+    pub fn is_favorite(&self, team_code: &str) -> bool {
+        self.favorite_teams
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(team_code))
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
+            strict: false,
+            warnings: Vec::new(),
             config_dir: get_config_dir(),
             data_dir: get_data_dir(),
+            log_level: None,
+            log_file: None,
+            socket: None,
             default_leagues: Vec::new(),
             spoil_results: false,
             spoil_matches: true,
+            spoiler_mask: "???".to_string(),
+            spoiler_mask_scope: MaskScope::Teams,
+            date_range: DateRange::All,
+            league_sort: LeagueSort::Priority,
+            state_format: StateFormat::Text,
+            state_glyphs: ["✓", "●", "○"].map(str::to_string),
+            winner_glyph: None,
+            conflict_window: None,
+            series_durations: [
+                Some(Duration::from_secs(60 * 60)),
+                Some(Duration::from_secs(150 * 60)),
+                Some(Duration::from_secs(240 * 60)),
+            ],
             automatic_reload: true,
+            offline: false,
+            read_only_cache: false,
+            refresh: false,
+            source: crate::net::Source::Network,
+            tick_rate: Some(Duration::from_secs(1)),
+            max_fps: Some(60),
+            refresh_interval: None,
+            live_refresh_interval: Some(Duration::from_secs(90)),
+            request_limit: Some(60),
+            danger_accept_invalid_certs: false,
+            ca_bundle: None,
+            mouse: false,
+            type_ahead: false,
+            stream_locale: "en-US".to_string(),
+            player: "mpv {url}".to_string(),
+            player_terminal: false,
+            hyperlinks: false,
+            strings: strings::Strings::default(),
+            color: ColorMode::Auto,
+            truecolor: true,
+            terminal_title: false,
+            summary: false,
+            month_headers: false,
+            live_banner: false,
+            league_counts: false,
+            upcoming_leagues_only: false,
+            recent_leagues: 5,
+            scroll_off: None,
+            padding: 0,
+            column_spacing: 0,
+            hidden_regions: Vec::new(),
+            favorite_teams: Vec::new(),
+            team_overrides: HashMap::new(),
+            league_groups: Vec::new(),
+            notifications: Notifications::default(),
+            hooks: Hooks::default(),
+            #[cfg(feature = "tui")]
             keybindings: KeyBindings::default(),
+            #[cfg(feature = "tui")]
             style: Styles::default(),
         }
     }
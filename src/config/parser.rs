@@ -1,17 +1,30 @@
 use std::char;
 use std::ops::{Deref, DerefMut};
-use std::{collections::HashMap, fs::read_to_string, path::Path};
-
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use ratatui::style::{Color, Modifier, Style};
-use ratatui::widgets::BorderType;
+use std::time::Duration;
+use std::{
+    collections::HashMap,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveTime;
 use tracing::*;
+#[cfg(feature = "tui")]
+use tracing_subscriber::EnvFilter;
 
 use super::error::Error;
-use crate::config::Config;
-use crate::event::AppEvent;
-
-use super::utils::{get_border_connections, get_config_dir, get_data_dir};
+#[cfg(feature = "tui")]
+use super::{
+    keys::{parse_command, parse_key_event},
+    style::parse_style_section,
+};
+use crate::config::{
+    ColorMode, Config, DateRange, LeagueSort, MaskScope, NotificationRule, PROJECT_NAME,
+    StateFormat, TeamOverride,
+    strings::{LANGUAGES, Strings},
+};
+
+use super::utils::{get_config_dir, get_data_dir, profile_dir};
 
 const SEPERATOR: char = '=';
 
@@ -32,84 +45,851 @@ impl DerefMut for RawConfig {
     }
 }
 
+const SECTIONS: &[&str] = &[
+    "settings",
+    "favorites",
+    "teams",
+    "groups",
+    "notifications",
+    "hooks",
+    "keybindings",
+    "style",
+];
+
 impl Config {
-    pub fn new() -> Result<Self, Error> {
-        let config_path = get_config_dir().join("config");
-        if config_path.exists() {
-            Config::from_file(config_path)
+    /// Loads the config at `path`, or the default config file of `profile` if `None`.
+    pub fn new(path: Option<&Path>, profile: Option<&str>) -> Result<Self, Error> {
+        Config::load(path, profile, false)
+    }
+
+    /// Loads the config strictly, where unknown keys and sections are errors.
+    pub fn check(path: Option<&Path>, profile: Option<&str>) -> Result<Self, Error> {
+        Config::load(path, profile, true)
+    }
+
+    // The config file used when none is given
+    pub fn default_path(profile: Option<&str>) -> PathBuf {
+        profile_dir(get_config_dir(), profile).join("config")
+    }
+
+    fn load(path: Option<&Path>, profile: Option<&str>, strict: bool) -> Result<Self, Error> {
+        // An explicitly given config file has to exist
+        if let Some(path) = path {
+            let mut config = Config::from_file(path, strict)?;
+            config.data_dir = profile_dir(config.data_dir, profile);
+            config.apply_env_overrides()?;
+            config.finish();
+            return Ok(config);
+        }
+        let config_path = Config::default_path(profile);
+        let mut config = if config_path.exists() {
+            Config::from_file(config_path, strict)?
         } else {
             info!("Found no config file, proceeding with default values");
-            Ok(Config::default())
+            Config {
+                config_dir: profile_dir(get_config_dir(), profile),
+                ..Config::default()
+            }
+        };
+        config.data_dir = profile_dir(config.data_dir, profile);
+        config.apply_env_overrides()?;
+        config.finish();
+        Ok(config)
+    }
+
+    // Settings that depend on the whole config, like styles from a theme
+    fn finish(&mut self) {
+        #[cfg(feature = "tui")]
+        if !self.truecolor {
+            self.style.basic_colors();
+        }
+    }
+
+    // Applies `LOL_CAL_*` environment variables on top of the config file,
+    // Ex: `LOL_CAL_SPOIL_RESULTS=false`. The directories are handled in `utils`
+    fn apply_env_overrides(&mut self) -> Result<(), Error> {
+        let prefix = format!("{}_", PROJECT_NAME.as_str());
+        let mut overrides: Vec<(String, String)> = std::env::vars()
+            .filter_map(|(key, value)| {
+                key.strip_prefix(&prefix)
+                    .map(|key| (key.to_lowercase(), value))
+            })
+            .collect();
+        overrides.sort();
+
+        for (key, value) in overrides {
+            match key.as_str() {
+                "config_dir" | "data_dir" | "log_level" => {}
+                _ => match parse_setting(self, &key, value.trim()) {
+                    Err(Error::UnknownKey(_, _)) => {
+                        warn!(
+                            "Ignoring unknown environment variable '{}{}'",
+                            prefix,
+                            key.to_uppercase()
+                        )
+                    }
+                    result => result?,
+                },
+            }
         }
+        Ok(())
     }
 
-    fn from_file<P: AsRef<Path>>(path: P) -> Result<Config, Error> {
+    // In lenient mode unknown keys and sections are collected as warnings
+    pub(super) fn lenient(&mut self, result: Result<(), Error>) -> Result<(), Error> {
+        match result {
+            Err(e @ (Error::UnknownKey(_, _) | Error::UnknownSection(_))) if !self.strict => {
+                warn!("{}", e);
+                self.warnings.push(e.to_string());
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    fn from_file<P: AsRef<Path>>(path: P, strict: bool) -> Result<Config, Error> {
         let config_file = path.as_ref();
         if !config_file.exists() {
             return Err(Error::NoConfigFile(
                 config_file.to_string_lossy().into_owned(),
             ));
         }
-        let mut config = Config::default();
-
-        config.config_dir = path.as_ref().to_path_buf();
-        config.data_dir = get_data_dir();
+        let mut config = Config {
+            config_dir: config_file
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(get_config_dir),
+            data_dir: get_data_dir(),
+            ..Default::default()
+        };
 
         let raw_config = raw_from_file(config_file)?;
 
         info!("{:?}", raw_config);
 
-        if let Some(style) = raw_config.get("settings") {
-            for (raw_key, raw_value) in style {
-                match raw_key.as_str() {
-                    "default_leagues" => {
-                        config.default_leagues =
-                            raw_value.split(',').map(|s| s.trim().to_string()).collect()
+        // Strictness has to be known before any other key is parsed
+        config.strict = strict;
+        if let Some(settings) = raw_config.get("settings") {
+            for (_, raw_value) in settings.iter().filter(|(key, _)| key == "strict") {
+                config.strict |= parse_bool(raw_value)?;
+            }
+        }
+
+        let mut sections: Vec<&String> = raw_config.keys().collect();
+        sections.sort();
+        for section in sections {
+            if !SECTIONS.contains(&section.as_str()) && rule_name(section).is_none() {
+                let result = Err(Error::UnknownSection(section.to_string()));
+                config.lenient(result)?;
+            }
+        }
+
+        if let Some(settings) = raw_config.get("settings") {
+            for (raw_key, raw_value) in settings {
+                let result = parse_setting(&mut config, raw_key, raw_value);
+                config.lenient(result)?;
+            }
+        }
+
+        if let Some(favorites) = raw_config.get("favorites") {
+            for (raw_key, raw_value) in favorites {
+                let result = match raw_key.as_str() {
+                    "teams" => {
+                        config.favorite_teams =
+                            raw_value.split(',').map(|s| s.trim().to_string()).collect();
+                        Ok(())
                     }
-                    "spoil_results" => config.spoil_results = parse_bool(raw_value)?,
-                    "spoil_matches" => config.spoil_matches = parse_bool(raw_value)?,
-                    "automatic_reload" => config.automatic_reload = parse_bool(raw_value)?,
-                    _ => {
-                        return Err(Error::UnknownKey(
-                            raw_key.to_string(),
-                            "settings".to_string(),
-                        ));
+                    _ => Err(Error::UnknownKey(
+                        raw_key.to_string(),
+                        "favorites".to_string(),
+                    )),
+                };
+                config.lenient(result)?;
+            }
+        }
+
+        if let Some(teams) = raw_config.get("teams") {
+            for (raw_key, raw_value) in teams {
+                let result = parse_team_override(raw_value)
+                    .map(|team| config.team_overrides.insert(raw_key.to_uppercase(), team))
+                    .map(|_| ());
+                config.lenient(result)?;
+            }
+        }
+
+        if let Some(groups) = raw_config.get("groups") {
+            for (raw_key, raw_value) in groups {
+                config
+                    .league_groups
+                    .retain(|(name, _)| !name.eq_ignore_ascii_case(raw_key));
+                config
+                    .league_groups
+                    .push((raw_key.to_string(), parse_list(raw_value)));
+            }
+        }
+
+        if let Some(notifications) = raw_config.get("notifications") {
+            for (raw_key, raw_value) in notifications {
+                let result = match raw_key.as_str() {
+                    "enabled" => parse_bool(raw_value).map(|b| config.notifications.enabled = b),
+                    "schedule_changes" => {
+                        parse_bool(raw_value).map(|b| config.notifications.schedule_changes = b)
                     }
+                    _ => parse_rule_key(
+                        &mut config.notifications.defaults,
+                        "notifications",
+                        raw_key,
+                        raw_value,
+                    ),
                 };
+                config.lenient(result)?;
             }
         }
 
+        // Named rules start out from the `[notifications]` section
+        let mut rule_sections: Vec<(&str, &String)> = raw_config
+            .keys()
+            .filter_map(|section| rule_name(section).map(|name| (name, section)))
+            .collect();
+        rule_sections.sort();
+        for (name, section) in rule_sections {
+            let mut rule = NotificationRule {
+                name: name.to_string(),
+                ..config.notifications.defaults.clone()
+            };
+            for (raw_key, raw_value) in &raw_config[section] {
+                let result = parse_rule_key(&mut rule, section, raw_key, raw_value);
+                config.lenient(result)?;
+            }
+            config.notifications.rules.push(rule);
+        }
+
+        if let Some(hooks) = raw_config.get("hooks") {
+            for (raw_key, raw_value) in hooks {
+                let result = parse_hook_key(&mut config, raw_key, raw_value);
+                config.lenient(result)?;
+            }
+        }
+
+        // Without the TUI nothing reads them, so the sections are skipped
+        #[cfg(feature = "tui")]
         if let Some(binds) = raw_config.get("keybindings") {
             for (raw_key, raw_command) in binds {
                 let key_event = parse_key_event(raw_key)?;
-                let command = parse_command(&raw_command)?;
+                let command = parse_command(&config, raw_command)?;
                 config.keybindings.insert(key_event, command);
             }
         }
 
+        #[cfg(feature = "tui")]
         if let Some(style) = raw_config.get("style") {
-            for (raw_key, raw_style) in style {
-                match raw_key.as_str() {
-                    "default" => config.style.default = parse_style(raw_style)?,
-                    "highlight" => config.style.highlight = parse_style(raw_style)?,
-                    "selected" => config.style.selected = parse_style(raw_style)?,
-                    "winner" => config.style.winner = parse_optional_style(raw_style)?,
-                    "loser" => config.style.loser = parse_optional_style(raw_style)?,
-                    "border" => {
-                        config.style.border = parse_border_type(raw_style)?;
-                        config.style.border_set = get_border_connections(config.style.border);
-                    }
-                    _ => {
-                        return Err(Error::UnknownKey(raw_key.to_string(), "style".to_string()));
-                    }
-                };
-            }
+            parse_style_section(&mut config, style)?;
         }
 
         Ok(config)
     }
 }
 
+// A key of the `[settings]` section. The parser and the generated default config both read
+// this table, so a setting cannot be parsed without being written to the default config
+pub struct Setting {
+    pub key: &'static str,
+    pub description: &'static str,
+    // The value as written in the config file, empty when unset
+    pub value: fn(&Config) -> String,
+    pub parse: fn(&mut Config, &str) -> Result<(), Error>,
+}
+
+pub const SETTINGS: &[Setting] = &[
+    Setting {
+        key: "strict",
+        description: "fail on unknown keys and sections instead of warning about them",
+        value: |c| c.strict.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.strict |= b),
+    },
+    Setting {
+        key: "default_leagues",
+        description: "a comma separated list of leagues that are loaded on entry, Ex: LEC, LPL",
+        value: |c| c.default_leagues.join(", "),
+        parse: |c, raw| {
+            c.default_leagues = raw.split(',').map(|s| s.trim().to_string()).collect();
+            Ok(())
+        },
+    },
+    Setting {
+        key: "log_level",
+        description: "log filter, info when unset, overridden by the RUST_LOG and LOL_CAL_LOG_LEVEL environment variables",
+        value: |c| c.log_level.clone().unwrap_or_default(),
+        parse: |c, raw| {
+            #[cfg(feature = "tui")]
+            EnvFilter::try_new(raw).map_err(|_| Error::InvalidValue(raw.to_string()))?;
+            c.log_level = Some(raw.to_string());
+            Ok(())
+        },
+    },
+    Setting {
+        key: "log_file",
+        description: "log file, lol-cal.log when unset, relative paths are placed in the data directory",
+        value: |c| {
+            c.log_file
+                .as_ref()
+                .map_or(String::new(), |p| p.to_string_lossy().into_owned())
+        },
+        parse: |c, raw| {
+            c.log_file = Some(PathBuf::from(raw));
+            Ok(())
+        },
+    },
+    Setting {
+        key: "socket",
+        description: "Unix socket accepting commands from lol-cal send, relative paths are placed in the data directory, Ex: lol-cal.sock",
+        value: |c| format_path(c.socket.as_deref()),
+        parse: |c, raw| {
+            c.socket = match raw.to_lowercase().as_str() {
+                "none" => None,
+                _ => Some(PathBuf::from(raw)),
+            };
+            Ok(())
+        },
+    },
+    Setting {
+        key: "hidden_regions",
+        description: "regions whose leagues are left out of the leagues pane, Ex: OCEANIA",
+        value: |c| c.hidden_regions.join(", "),
+        parse: |c, raw| {
+            c.hidden_regions = parse_list(raw);
+            Ok(())
+        },
+    },
+    Setting {
+        key: "spoil_results",
+        description: "shows the results of completed matches",
+        value: |c| c.spoil_results.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.spoil_results = b),
+    },
+    Setting {
+        key: "spoil_matches",
+        description: "show the names of teams in unplayed matches",
+        value: |c| c.spoil_matches.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.spoil_matches = b),
+    },
+    Setting {
+        key: "spoiler_mask",
+        description: "shown instead of the teams of hidden matches, Ex: hidden",
+        value: |c| c.spoiler_mask.clone(),
+        parse: |c, raw| {
+            c.spoiler_mask = raw.to_string();
+            Ok(())
+        },
+    },
+    Setting {
+        key: "spoiler_mask_scope",
+        description: "what hidden matches hide, teams, time or the whole row",
+        value: |c| {
+            match c.spoiler_mask_scope {
+                MaskScope::Teams => "teams",
+                MaskScope::Time => "time",
+                MaskScope::Row => "row",
+            }
+            .to_string()
+        },
+        parse: |c, raw| {
+            c.spoiler_mask_scope = match raw.to_lowercase().as_str() {
+                "teams" => MaskScope::Teams,
+                "time" => MaskScope::Time,
+                "row" => MaskScope::Row,
+                _ => return Err(Error::InvalidValue(raw.to_string())),
+            };
+            Ok(())
+        },
+    },
+    Setting {
+        key: "automatic_reload",
+        description: "automatically fetch data when a league is selected",
+        value: |c| c.automatic_reload.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.automatic_reload = b),
+    },
+    Setting {
+        key: "offline",
+        description: "only use cached data, never fetching from the API",
+        value: |c| c.offline.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.offline = b),
+    },
+    Setting {
+        key: "read_only_cache",
+        description: "never write to the cache directory, fetched data is kept in memory instead",
+        value: |c| c.read_only_cache.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.read_only_cache = b),
+    },
+    Setting {
+        key: "tick_rate",
+        description: "how often time based updates happen, none turns them off",
+        value: |c| format_duration(c.tick_rate),
+        parse: |c, raw| parse_duration(raw).map(|d| c.tick_rate = d),
+    },
+    Setting {
+        key: "max_fps",
+        description: "most frames drawn a second, none draws every change",
+        value: |c| c.max_fps.map_or("none".to_string(), |fps| fps.to_string()),
+        parse: |c, raw| {
+            c.max_fps = match raw.to_lowercase().as_str() {
+                "none" => None,
+                lower => Some(
+                    lower
+                        .parse()
+                        .ok()
+                        .filter(|fps| *fps > 0)
+                        .ok_or_else(|| Error::InvalidValue(raw.to_string()))?,
+                ),
+            };
+            Ok(())
+        },
+    },
+    Setting {
+        key: "refresh_interval",
+        description: "re-fetch the active schedules on an interval, Ex: 90s, 5m, 1h",
+        value: |c| format_duration(c.refresh_interval),
+        parse: |c, raw| parse_duration(raw).map(|d| c.refresh_interval = d),
+    },
+    Setting {
+        key: "live_refresh_interval",
+        description: "re-fetch leagues with a match in progress on an interval",
+        value: |c| format_duration(c.live_refresh_interval),
+        parse: |c, raw| parse_duration(raw).map(|d| c.live_refresh_interval = d),
+    },
+    Setting {
+        key: "request_limit",
+        description: "most API requests sent a minute, the rest wait for their turn, none is unlimited",
+        value: |c| {
+            c.request_limit
+                .map_or("none".to_string(), |limit| limit.to_string())
+        },
+        parse: |c, raw| {
+            c.request_limit = match raw.to_lowercase().as_str() {
+                "none" => None,
+                lower => Some(
+                    lower
+                        .parse()
+                        .ok()
+                        .filter(|limit| *limit > 0)
+                        .ok_or_else(|| Error::InvalidValue(raw.to_string()))?,
+                ),
+            };
+            Ok(())
+        },
+    },
+    Setting {
+        key: "danger_accept_invalid_certs",
+        description: "do not verify TLS certificates, only for proxies that intercept TLS, this is insecure",
+        value: |c| c.danger_accept_invalid_certs.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.danger_accept_invalid_certs = b),
+    },
+    Setting {
+        key: "ca_bundle",
+        description: "file of PEM certificates to trust besides the system ones, relative to the config directory",
+        value: |c| format_path(c.ca_bundle.as_deref()),
+        parse: |c, raw| {
+            c.ca_bundle = match raw.to_lowercase().as_str() {
+                "none" => None,
+                _ => Some(c.config_dir.join(raw)),
+            };
+            Ok(())
+        },
+    },
+    Setting {
+        key: "mouse",
+        description: "capture the mouse, scrolling with the mouse wheel instead of selecting text",
+        value: |c| c.mouse.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.mouse = b),
+    },
+    Setting {
+        key: "type_ahead",
+        description: "unbound letters typed in the leagues pane jump to the first league starting with them",
+        value: |c| c.type_ahead.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.type_ahead = b),
+    },
+    Setting {
+        key: "stream_locale",
+        description: "preferred locale of streams and VODs opened with OpenLink",
+        value: |c| c.stream_locale.clone(),
+        parse: |c, raw| {
+            c.stream_locale = raw.to_string();
+            Ok(())
+        },
+    },
+    Setting {
+        key: "player",
+        description: "command that plays streams and VODs with WatchStream, {url} is replaced with the link",
+        value: |c| c.player.clone(),
+        parse: |c, raw| {
+            if raw.trim().is_empty() {
+                return Err(Error::InvalidValue(raw.to_string()));
+            }
+            c.player = raw.to_string();
+            Ok(())
+        },
+    },
+    Setting {
+        key: "player_terminal",
+        description: "run the player in the terminal, suspending the TUI, Ex: for streamlink",
+        value: |c| c.player_terminal.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.player_terminal = b),
+    },
+    Setting {
+        key: "hyperlinks",
+        description: "make the state of live and completed matches a clickable link, needs OSC 8 support",
+        value: |c| c.hyperlinks.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.hyperlinks = b),
+    },
+    Setting {
+        key: "truecolor",
+        description: "false maps RGB colors to the closest of the 16 basic colors",
+        value: |c| c.truecolor.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.truecolor = b),
+    },
+    Setting {
+        key: "color",
+        description: "never, auto or always, never keeps only modifiers like bold",
+        value: |c| {
+            match c.color {
+                ColorMode::Never => "never",
+                ColorMode::Auto => "auto",
+                ColorMode::Always => "always",
+            }
+            .to_string()
+        },
+        parse: |c, raw| {
+            c.color = match raw.to_lowercase().as_str() {
+                "never" => ColorMode::Never,
+                "auto" => ColorMode::Auto,
+                "always" => ColorMode::Always,
+                _ => return Err(Error::InvalidValue(raw.to_string())),
+            };
+            Ok(())
+        },
+    },
+    Setting {
+        key: "state_format",
+        description: "state column of the schedule: text, glyph or both",
+        value: |c| {
+            match c.state_format {
+                StateFormat::Text => "text",
+                StateFormat::Glyph => "glyph",
+                StateFormat::Both => "both",
+            }
+            .to_string()
+        },
+        parse: |c, raw| {
+            c.state_format = match raw.to_lowercase().as_str() {
+                "text" => StateFormat::Text,
+                "glyph" => StateFormat::Glyph,
+                "both" => StateFormat::Both,
+                _ => return Err(Error::InvalidValue(raw.to_string())),
+            };
+            Ok(())
+        },
+    },
+    Setting {
+        key: "state_glyphs",
+        description: "glyphs of completed, in progress and unstarted matches",
+        value: |c| c.state_glyphs.join(", "),
+        parse: |c, raw| {
+            c.state_glyphs = parse_list(raw)
+                .try_into()
+                .map_err(|_| Error::InvalidValue(raw.to_string()))?;
+            Ok(())
+        },
+    },
+    Setting {
+        key: "winner_glyph",
+        description: "shown next to the winner of a completed match when results are spoiled, Ex: 🏆",
+        value: |c| c.winner_glyph.clone().unwrap_or("none".to_string()),
+        parse: |c, raw| {
+            c.winner_glyph = match raw.to_lowercase().as_str() {
+                "none" | "" => None,
+                _ => Some(raw.to_string()),
+            };
+            Ok(())
+        },
+    },
+    Setting {
+        key: "date_range",
+        description: "days of the schedule shown: all, upcoming, today, week or weekend",
+        value: |c| c.date_range.name().to_string(),
+        parse: |c, raw| {
+            c.date_range =
+                DateRange::from_name(raw).ok_or_else(|| Error::InvalidValue(raw.to_string()))?;
+            Ok(())
+        },
+    },
+    Setting {
+        key: "league_sort",
+        description: "order of the leagues pane: priority, name or region",
+        value: |c| c.league_sort.name().to_string(),
+        parse: |c, raw| {
+            c.league_sort =
+                LeagueSort::from_name(raw).ok_or_else(|| Error::InvalidValue(raw.to_string()))?;
+            Ok(())
+        },
+    },
+    Setting {
+        key: "conflict_window",
+        description: "mark matches starting at most this far apart, Ex: 30m, none turns it off",
+        value: |c| format_duration(c.conflict_window),
+        parse: |c, raw| parse_duration(raw).map(|d| c.conflict_window = d),
+    },
+    Setting {
+        key: "duration_bo1",
+        description: "estimated length of a Bo1 series, none hides it",
+        value: |c| format_duration(c.series_durations[0]),
+        parse: |c, raw| parse_duration(raw).map(|d| c.series_durations[0] = d),
+    },
+    Setting {
+        key: "duration_bo3",
+        description: "estimated length of a Bo3 series, none hides it",
+        value: |c| format_duration(c.series_durations[1]),
+        parse: |c, raw| parse_duration(raw).map(|d| c.series_durations[1] = d),
+    },
+    Setting {
+        key: "duration_bo5",
+        description: "estimated length of a Bo5 series, none hides it",
+        value: |c| format_duration(c.series_durations[2]),
+        parse: |c, raw| parse_duration(raw).map(|d| c.series_durations[2] = d),
+    },
+    Setting {
+        key: "terminal_title",
+        description: "show the next match and a countdown to it in the terminal title",
+        value: |c| c.terminal_title.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.terminal_title = b),
+    },
+    Setting {
+        key: "summary",
+        description: "show the number of matches today, how many are live and the next kickoff",
+        value: |c| c.summary.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.summary = b),
+    },
+    Setting {
+        key: "month_headers",
+        description: "name the month and year above the first day of each month",
+        value: |c| c.month_headers.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.month_headers = b),
+    },
+    Setting {
+        key: "live_banner",
+        description: "list the matches in progress above the schedule",
+        value: |c| c.live_banner.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.live_banner = b),
+    },
+    Setting {
+        key: "league_counts",
+        description: "break the match count of the schedule header down by league",
+        value: |c| c.league_counts.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.league_counts = b),
+    },
+    Setting {
+        key: "upcoming_leagues_only",
+        description: "hide leagues without upcoming matches in their cached schedule",
+        value: |c| c.upcoming_leagues_only.to_string(),
+        parse: |c, raw| parse_bool(raw).map(|b| c.upcoming_leagues_only = b),
+    },
+    Setting {
+        key: "recent_leagues",
+        description: "recently activated leagues listed at the top of the leagues pane",
+        value: |c| c.recent_leagues.to_string(),
+        parse: |c, raw| parse_number(raw).map(|n| c.recent_leagues = n),
+    },
+    Setting {
+        key: "scroll_off",
+        description: "matches kept above the selection when jumping, or center",
+        value: |c| c.scroll_off.map_or("center".to_string(), |n| n.to_string()),
+        parse: |c, raw| {
+            c.scroll_off = match raw.to_lowercase().as_str() {
+                "center" | "centre" => None,
+                _ => Some(parse_number(raw)?),
+            };
+            Ok(())
+        },
+    },
+    Setting {
+        key: "padding",
+        description: "empty columns on both sides inside the panes",
+        value: |c| c.padding.to_string(),
+        parse: |c, raw| parse_number(raw).map(|n| c.padding = n),
+    },
+    Setting {
+        key: "column_spacing",
+        description: "empty columns between the time, teams and state of a match",
+        value: |c| c.column_spacing.to_string(),
+        parse: |c, raw| parse_number(raw).map(|n| c.column_spacing = n),
+    },
+    Setting {
+        key: "language",
+        description: "a bundled language, Ex: de, es or sv, or a file in the languages directory",
+        value: |c| {
+            LANGUAGES
+                .iter()
+                .find(|language| Strings::bundled(language).as_ref() == Some(&c.strings))
+                .map_or(String::new(), |language| language.to_string())
+        },
+        parse: parse_language,
+    },
+    Setting {
+        key: "theme",
+        description: "a theme file, either a path or a file name in the themes directory",
+        value: |_| String::new(),
+        parse: parse_theme,
+    },
+];
+
+fn parse_setting(config: &mut Config, raw_key: &str, raw_value: &str) -> Result<(), Error> {
+    match SETTINGS.iter().find(|setting| setting.key == raw_key) {
+        Some(setting) => (setting.parse)(config, raw_value),
+        None => Err(Error::UnknownKey(
+            raw_key.to_string(),
+            "settings".to_string(),
+        )),
+    }
+}
+
+#[cfg(not(feature = "tui"))]
+fn parse_theme(_config: &mut Config, _raw_value: &str) -> Result<(), Error> {
+    Ok(())
+}
+
+#[cfg(feature = "tui")]
+fn parse_theme(config: &mut Config, raw_value: &str) -> Result<(), Error> {
+    let theme_path = resolve_file(&config.config_dir, "themes", raw_value)
+        .ok_or_else(|| Error::NoThemeFile(raw_value.to_string()))?;
+    let raw_theme = raw_from_file(theme_path)?;
+    // Themes may either use a [style] header or list keys bare
+    for section in ["settings", "style"] {
+        if let Some(style) = raw_theme.get(section) {
+            parse_style_section(config, style)?;
+        }
+    }
+    Ok(())
+}
+
+// `[notifications.NAME]` sections define notification rules
+fn rule_name(section: &str) -> Option<&str> {
+    section
+        .strip_prefix("notifications.")
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+}
+
+fn parse_rule_key(
+    rule: &mut NotificationRule,
+    section: &str,
+    raw_key: &str,
+    raw_value: &str,
+) -> Result<(), Error> {
+    match raw_key {
+        "before" => rule.before = parse_duration(raw_value)?,
+        "leagues" => rule.leagues = parse_list(raw_value),
+        "teams" => rule.teams = parse_list(raw_value),
+        "favorites_only" => rule.favorites_only = parse_bool(raw_value)?,
+        "match_started" => rule.match_started = parse_bool(raw_value)?,
+        "match_finished" => rule.match_finished = parse_bool(raw_value)?,
+        "quiet_hours" => rule.quiet_hours = parse_time_range(raw_value)?,
+        _ => {
+            return Err(Error::UnknownKey(raw_key.to_string(), section.to_string()));
+        }
+    };
+    Ok(())
+}
+
+fn parse_hook_key(config: &mut Config, raw_key: &str, raw_value: &str) -> Result<(), Error> {
+    let command = Some(raw_value.to_string());
+    match raw_key {
+        "on_match_start" => config.hooks.on_match_start = command,
+        "on_match_end" => config.hooks.on_match_end = command,
+        "on_reminder" => config.hooks.on_reminder = command,
+        _ => {
+            return Err(Error::UnknownKey(raw_key.to_string(), "hooks".to_string()));
+        }
+    };
+    Ok(())
+}
+
+// A team code, optionally followed by a name, Ex: `MAD` or `MAD, MAD Lions`
+fn parse_team_override(line: &str) -> Result<TeamOverride, Error> {
+    let (short, name) = match line.split_once(',') {
+        Some((short, name)) => (short.trim(), Some(name.trim())),
+        None => (line.trim(), None),
+    };
+    if short.is_empty() || name.is_some_and(str::is_empty) {
+        return Err(Error::InvalidValue(line.to_string()));
+    }
+    Ok(TeamOverride {
+        short: short.to_string(),
+        name: name.map(str::to_string),
+    })
+}
+
+fn parse_list(line: &str) -> Vec<String> {
+    line.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+// Parses ranges of local times such as `23:00-08:00`, where `none` disables
+fn parse_time_range(line: &str) -> Result<Option<(NaiveTime, NaiveTime)>, Error> {
+    if line.eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+    let invalid = || Error::InvalidValue(line.to_string());
+    let (start, end) = line.split_once('-').ok_or_else(invalid)?;
+    let parse = |time: &str| NaiveTime::parse_from_str(time.trim(), "%H:%M").map_err(|_| invalid());
+    Ok(Some((parse(start)?, parse(end)?)))
+}
+
+// A path, or the name of a file in `dir` of the config directory
+fn resolve_file(config_dir: &Path, dir: &str, raw: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(raw);
+    if path.is_absolute() || raw.contains(std::path::MAIN_SEPARATOR) {
+        if path.exists() {
+            return Some(path);
+        }
+    } else {
+        let dir = config_dir.join(dir);
+        for candidate in [dir.join(raw), dir.join(format!("{}.conf", raw))] {
+            if candidate.exists() {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+// A bundled language, or a file of translated strings where missing strings stay English
+fn parse_language(config: &mut Config, raw_value: &str) -> Result<(), Error> {
+    if let Some(strings) = Strings::bundled(raw_value) {
+        config.strings = strings;
+        return Ok(());
+    }
+    let path = resolve_file(&config.config_dir, "languages", raw_value)
+        .ok_or_else(|| Error::NoLanguageFile(raw_value.to_string()))?;
+    let raw_language = raw_from_file(path)?;
+    let mut strings = Strings::default();
+    // Like themes, strings may be under a [strings] header or listed bare
+    for section in ["settings", "strings"] {
+        for (key, value) in raw_language.get(section).into_iter().flatten() {
+            let result = match strings.get_mut(key) {
+                Some(string) => {
+                    *string = value.to_string();
+                    Ok(())
+                }
+                None => Err(Error::UnknownKey(key.to_string(), "strings".to_string())),
+            };
+            config.lenient(result)?;
+        }
+    }
+    config.strings = strings;
+    Ok(())
+}
+
 fn raw_from_file<P: AsRef<Path>>(path: P) -> Result<RawConfig, Error> {
     let content = read_to_string(path)?;
     let mut section = String::new();
@@ -157,209 +937,73 @@ fn raw_from_file<P: AsRef<Path>>(path: P) -> Result<RawConfig, Error> {
     Ok(sections)
 }
 
-fn parse_key_event(raw: &str) -> Result<KeyEvent, Error> {
-    let raw_lower = raw.to_ascii_lowercase();
-    let (remaining, modifiers) = extract_modifiers(&raw_lower);
-    parse_key_code_with_modifiers(remaining, modifiers)
+fn parse_number<T: std::str::FromStr>(line: &str) -> Result<T, Error> {
+    line.parse()
+        .map_err(|_| Error::InvalidValue(line.to_string()))
 }
 
-fn parse_command(raw: &str) -> Result<AppEvent, Error> {
-    use AppEvent::*;
-
-    Ok(match raw {
-        "Quit" => Quit,
-        "Up" => Up,
-        "Down" => Down,
-        "Left" => Left,
-        "Right" => Right,
-        "Select" => Select,
-        "GotoToday" => GotoToday,
-        "ToggleSpoilResults" => ToggleSpoilResults,
-        "ToggleSpoilMatches" => ToggleSpoilMatches,
-        "ReloadLeagues" => ReloadLeagues,
-        "ReloadSchedule" => ReloadSchedule,
-        _ => return Err(Error::InvalidCommand(raw.to_string())),
-    })
-}
-
-fn extract_modifiers(raw: &str) -> (&str, KeyModifiers) {
-    let mut modifiers = KeyModifiers::empty();
-    let mut current = raw;
-
-    loop {
-        match current {
-            rest if rest.starts_with("ctrl-") => {
-                modifiers.insert(KeyModifiers::CONTROL);
-                current = &rest[5..];
-            }
-            rest if rest.starts_with("alt-") => {
-                modifiers.insert(KeyModifiers::ALT);
-                current = &rest[4..];
-            }
-            rest if rest.starts_with("shift-") => {
-                modifiers.insert(KeyModifiers::SHIFT);
-                current = &rest[6..];
-            }
-            _ => break, // break out of the loop if no known prefix is detected
-        };
+fn parse_bool(line: &str) -> Result<bool, Error> {
+    match line.to_lowercase().as_str() {
+        "yes" | "true" => Ok(true),
+        "no" | "false" => Ok(false),
+        _ => Err(Error::InvalidBool(line.to_string())),
     }
-
-    (current, modifiers)
-}
-
-fn parse_key_code_with_modifiers(
-    raw: &str,
-    mut modifiers: KeyModifiers,
-) -> Result<KeyEvent, Error> {
-    let c = match raw {
-        "esc" => KeyCode::Esc,
-        "enter" => KeyCode::Enter,
-        "left" => KeyCode::Left,
-        "right" => KeyCode::Right,
-        "up" => KeyCode::Up,
-        "down" => KeyCode::Down,
-        "home" => KeyCode::Home,
-        "end" => KeyCode::End,
-        "pageup" => KeyCode::PageUp,
-        "pagedown" => KeyCode::PageDown,
-        "backtab" => {
-            modifiers.insert(KeyModifiers::SHIFT);
-            KeyCode::BackTab
-        }
-        "backspace" => KeyCode::Backspace,
-        "delete" => KeyCode::Delete,
-        "insert" => KeyCode::Insert,
-        "f1" => KeyCode::F(1),
-        "f2" => KeyCode::F(2),
-        "f3" => KeyCode::F(3),
-        "f4" => KeyCode::F(4),
-        "f5" => KeyCode::F(5),
-        "f6" => KeyCode::F(6),
-        "f7" => KeyCode::F(7),
-        "f8" => KeyCode::F(8),
-        "f9" => KeyCode::F(9),
-        "f10" => KeyCode::F(10),
-        "f11" => KeyCode::F(11),
-        "f12" => KeyCode::F(12),
-        "space" => KeyCode::Char(' '),
-        "hyphen" => KeyCode::Char('-'),
-        "minus" => KeyCode::Char('-'),
-        "tab" => KeyCode::Tab,
-        c if c.len() == 1 => {
-            let mut c = c.chars().next().unwrap();
-            if modifiers.contains(KeyModifiers::SHIFT) {
-                c = c.to_ascii_uppercase();
-            }
-            KeyCode::Char(c)
-        }
-        _ => return Err(Error::InvalidKeybind(raw.to_string())),
-    };
-    Ok(KeyEvent::new(c, modifiers))
 }
 
-fn parse_style(line: &str) -> Result<Style, Error> {
-    let (foreground, background) =
-        line.split_at(line.to_lowercase().find("on ").unwrap_or(line.len()));
-    let foreground = process_color_string(foreground);
-    let background = process_color_string(&background.replace("on ", ""));
-
-    let mut style = Style::default();
-    if !foreground.0.is_empty() {
-        let fg = parse_color(&foreground.0)?;
-        style = style.fg(fg);
+// Parses durations such as `90`, `90s`, `5m` or `1h`, where `none` and `0` disable
+pub fn parse_duration(line: &str) -> Result<Option<Duration>, Error> {
+    let line = line.to_lowercase();
+    if line == "none" {
+        return Ok(None);
     }
-    if !background.0.is_empty() {
-        let bg = parse_color(&background.0)?;
-        style = style.bg(bg);
+    let (number, unit) = line.split_at(
+        line.find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(line.len()),
+    );
+    let number: u64 = number
+        .parse()
+        .map_err(|_| Error::InvalidValue(line.to_string()))?;
+    let seconds = match unit.trim() {
+        "" | "s" => Some(number),
+        "m" => number.checked_mul(60),
+        "h" => number.checked_mul(60 * 60),
+        _ => None,
     }
-    style = style.add_modifier(foreground.1 | background.1);
-    Ok(style)
-}
-
-fn parse_optional_style(line: &str) -> Result<Option<Style>, Error> {
-    if line.to_lowercase() == "none" {
+    .ok_or_else(|| Error::InvalidValue(line.to_string()))?;
+    if seconds == 0 {
         Ok(None)
     } else {
-        Ok(Some(parse_style(line)?))
+        Ok(Some(Duration::from_secs(seconds)))
     }
 }
 
-fn process_color_string(color_str: &str) -> (String, Modifier) {
-    let color = color_str
-        .replace("bold ", "")
-        // .replace("underline ", "")
-        .replace("inverse ", "")
-        .trim()
-        .to_string();
-
-    let mut modifiers = Modifier::empty();
-    /*
-        if color_str.contains("underline") {
-            modifiers |= Modifier::UNDERLINED;
-        }
-    */
-    if color_str.contains("bold") {
-        modifiers |= Modifier::BOLD;
-    }
-    if color_str.contains("inverse") {
-        modifiers |= Modifier::REVERSED;
-    }
-
-    (color, modifiers)
-}
-
-fn parse_color(s: &str) -> Result<Color, Error> {
-    if let Some(rgb) = parse_rgb(s) {
-        return Ok(Color::Rgb(rgb.0, rgb.1, rgb.2));
-    }
-    match s {
-        "black" => Ok(Color::Indexed(0)),
-        "red" => Ok(Color::Indexed(1)),
-        "green" => Ok(Color::Indexed(2)),
-        "yellow" => Ok(Color::Indexed(3)),
-        "blue" => Ok(Color::Indexed(4)),
-        "magenta" => Ok(Color::Indexed(5)),
-        "cyan" => Ok(Color::Indexed(6)),
-        "gray" => Ok(Color::Indexed(7)),
-        "bright black" => Ok(Color::Indexed(8)),
-        "bright red" => Ok(Color::Indexed(9)),
-        "bright green" => Ok(Color::Indexed(10)),
-        "bright yellow" => Ok(Color::Indexed(11)),
-        "bright blue" => Ok(Color::Indexed(12)),
-        "bright magenta" => Ok(Color::Indexed(13)),
-        "bright cyan" => Ok(Color::Indexed(14)),
-        "white" => Ok(Color::Indexed(15)),
-        _ => Err(Error::InvalidColor(s.to_string())),
+// The inverse of `parse_duration`
+pub fn format_duration(duration: Option<Duration>) -> String {
+    match duration {
+        Some(duration) => format!("{}s", duration.as_secs()),
+        None => "none".to_string(),
     }
 }
 
-fn parse_rgb(s: &str) -> Option<(u8, u8, u8)> {
-    if !s.starts_with('#') || s.len() != 7 {
-        return None;
-    }
-
-    let r = u8::from_str_radix(&s[1..3], 16).ok()?;
-    let g = u8::from_str_radix(&s[3..5], 16).ok()?;
-    let b = u8::from_str_radix(&s[5..7], 16).ok()?;
-
-    Some((r, g, b))
-}
-
-fn parse_border_type(line: &str) -> Result<Option<BorderType>, Error> {
-    match line.to_lowercase().as_str() {
-        "plain" => Ok(Some(BorderType::Plain)),
-        "rounded" => Ok(Some(BorderType::Rounded)),
-        "double" => Ok(Some(BorderType::Double)),
-        "thick" => Ok(Some(BorderType::Thick)),
-        "none" => Ok(None),
-        _ => Err(Error::InvalidBorder(line.to_string())),
-    }
+fn format_path(path: Option<&Path>) -> String {
+    path.map_or("none".to_string(), |path| {
+        path.to_string_lossy().into_owned()
+    })
 }
 
-fn parse_bool(line: &str) -> Result<bool, Error> {
-    match line.to_lowercase().as_str() {
-        "yes" | "true" => Ok(true),
-        "no" | "false" => Ok(false),
-        _ => return Err(Error::InvalidBool(line.to_string())),
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuses_durations_that_overflow() {
+        assert_eq!(
+            parse_duration("2h").unwrap(),
+            Some(Duration::from_secs(7200))
+        );
+        assert!(matches!(
+            parse_duration("99999999999999999h"),
+            Err(Error::InvalidValue(_))
+        ));
     }
 }
@@ -0,0 +1,453 @@
+use crate::models::{MatchState, ScheduleChanges, StratType};
+
+// Text shown in the TUI, English unless another language is set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Strings {
+    pub schedule: String,
+    pub leagues: String,
+    pub best_of: String,
+    pub play_all: String,
+    pub completed: String,
+    pub in_progress: String,
+    pub unstarted: String,
+    pub vs: String,
+    pub recent: String,
+    // Folded days, `{}` is replaced with the count
+    pub hidden_one: String,
+    pub hidden: String,
+    // The summary line, Ex: "Today: 4 matches, 1 live, next at 18:00"
+    pub today_one: String,
+    pub today: String,
+    pub live_now: String,
+    pub next_at: String,
+    pub next: String,
+    pub nothing_upcoming: String,
+    // The live banner
+    pub live: String,
+    // The game of a live match, `{}` is replaced with its number
+    pub game: String,
+    // What a refresh changed in a schedule
+    pub rescheduled_one: String,
+    pub rescheduled: String,
+    pub added_one: String,
+    pub added: String,
+    pub removed_one: String,
+    pub removed: String,
+    // The details popup, `{time}` and similar are replaced with what they name
+    pub details: String,
+    pub ends_around: String,
+    pub head_to_head_hidden: String,
+    pub head_to_head_one: String,
+    pub head_to_head: String,
+    pub never_played: String,
+    pub not_started: String,
+    pub loading: String,
+    pub picks_hidden: String,
+    pub no_games: String,
+    pub game_title: String,
+    pub patch: String,
+    pub no_picks: String,
+    pub no_stats: String,
+    pub loading_stats: String,
+}
+
+impl Default for Strings {
+    fn default() -> Self {
+        Strings {
+            schedule: "Schedule".to_string(),
+            leagues: "Leagues".to_string(),
+            best_of: "Best of".to_string(),
+            play_all: "Play all".to_string(),
+            completed: "Completed".to_string(),
+            in_progress: "In progress".to_string(),
+            unstarted: "Unstarted".to_string(),
+            vs: "vs".to_string(),
+            recent: "Recent".to_string(),
+            hidden_one: "1 match, hidden".to_string(),
+            hidden: "{} matches, hidden".to_string(),
+            today_one: "Today: 1 match".to_string(),
+            today: "Today: {} matches".to_string(),
+            live_now: "{} live".to_string(),
+            next_at: "next at {}".to_string(),
+            next: "next {}".to_string(),
+            nothing_upcoming: "nothing upcoming".to_string(),
+            live: "Live".to_string(),
+            game: "game {}".to_string(),
+            rescheduled_one: "1 match rescheduled".to_string(),
+            rescheduled: "{} matches rescheduled".to_string(),
+            added_one: "1 new match".to_string(),
+            added: "{} new matches".to_string(),
+            removed_one: "1 match removed".to_string(),
+            removed: "{} matches removed".to_string(),
+            details: "Details".to_string(),
+            ends_around: "Ends around {time}, after about {length}".to_string(),
+            head_to_head_hidden: "Head-to-head is hidden while results are not spoiled".to_string(),
+            head_to_head_one: "Head-to-head: {record} in 1 match".to_string(),
+            head_to_head: "Head-to-head: {record} in {} matches".to_string(),
+            never_played: "{team1} and {team2} have not played each other".to_string(),
+            not_started: "Not started yet".to_string(),
+            loading: "Loading...".to_string(),
+            picks_hidden: "Picks are hidden while results are not spoiled".to_string(),
+            no_games: "No games played yet".to_string(),
+            game_title: "Game {}".to_string(),
+            patch: "patch {}".to_string(),
+            no_picks: "No picks available".to_string(),
+            no_stats: "No stats available".to_string(),
+            loading_stats: "Loading stats...".to_string(),
+        }
+    }
+}
+
+pub const LANGUAGES: &[&str] = &["en", "de", "es", "sv"];
+
+// Translations that ship with the program, as keys of `get_mut` and their text
+const GERMAN: &[(&str, &str)] = &[
+    ("schedule", "Spielplan"),
+    ("leagues", "Ligen"),
+    ("best_of", "Best of"),
+    ("play_all", "Alle Spiele"),
+    ("completed", "Beendet"),
+    ("in_progress", "Läuft"),
+    ("unstarted", "Ausstehend"),
+    ("vs", "vs"),
+    ("recent", "Zuletzt"),
+    ("hidden_one", "1 Spiel ausgeblendet"),
+    ("hidden", "{} Spiele ausgeblendet"),
+    ("today_one", "Heute: 1 Spiel"),
+    ("today", "Heute: {} Spiele"),
+    ("live_now", "{} live"),
+    ("next_at", "nächstes um {}"),
+    ("next", "nächstes {}"),
+    ("nothing_upcoming", "nichts geplant"),
+    ("live", "Live"),
+    ("game", "Spiel {}"),
+    ("rescheduled_one", "1 Spiel verschoben"),
+    ("rescheduled", "{} Spiele verschoben"),
+    ("added_one", "1 neues Spiel"),
+    ("added", "{} neue Spiele"),
+    ("removed_one", "1 Spiel entfernt"),
+    ("removed", "{} Spiele entfernt"),
+    ("details", "Details"),
+    ("ends_around", "Endet gegen {time}, nach etwa {length}"),
+    (
+        "head_to_head_hidden",
+        "Der direkte Vergleich ist ausgeblendet, solange Ergebnisse verborgen sind",
+    ),
+    (
+        "head_to_head_one",
+        "Direkter Vergleich: {record} in 1 Spiel",
+    ),
+    ("head_to_head", "Direkter Vergleich: {record} in {} Spielen"),
+    (
+        "never_played",
+        "{team1} und {team2} haben noch nicht gegeneinander gespielt",
+    ),
+    ("not_started", "Noch nicht begonnen"),
+    ("loading", "Lädt..."),
+    (
+        "picks_hidden",
+        "Picks sind ausgeblendet, solange Ergebnisse verborgen sind",
+    ),
+    ("no_games", "Noch keine Spiele gespielt"),
+    ("game_title", "Spiel {}"),
+    ("patch", "Patch {}"),
+    ("no_picks", "Keine Picks verfügbar"),
+    ("no_stats", "Keine Statistiken verfügbar"),
+    ("loading_stats", "Lädt Statistiken..."),
+];
+
+const SPANISH: &[(&str, &str)] = &[
+    ("schedule", "Calendario"),
+    ("leagues", "Ligas"),
+    ("best_of", "Al mejor de"),
+    ("play_all", "Todos"),
+    ("completed", "Finalizado"),
+    ("in_progress", "En curso"),
+    ("unstarted", "Por jugar"),
+    ("vs", "vs"),
+    ("recent", "Recientes"),
+    ("hidden_one", "1 partido oculto"),
+    ("hidden", "{} partidos ocultos"),
+    ("today_one", "Hoy: 1 partido"),
+    ("today", "Hoy: {} partidos"),
+    ("live_now", "{} en directo"),
+    ("next_at", "siguiente a las {}"),
+    ("next", "siguiente {}"),
+    ("nothing_upcoming", "nada pendiente"),
+    ("live", "En directo"),
+    ("game", "partida {}"),
+    ("rescheduled_one", "1 partido reprogramado"),
+    ("rescheduled", "{} partidos reprogramados"),
+    ("added_one", "1 partido nuevo"),
+    ("added", "{} partidos nuevos"),
+    ("removed_one", "1 partido eliminado"),
+    ("removed", "{} partidos eliminados"),
+    ("details", "Detalles"),
+    (
+        "ends_around",
+        "Termina sobre las {time}, tras unos {length}",
+    ),
+    (
+        "head_to_head_hidden",
+        "El historial está oculto mientras no se muestren los resultados",
+    ),
+    ("head_to_head_one", "Historial: {record} en 1 partido"),
+    ("head_to_head", "Historial: {record} en {} partidos"),
+    ("never_played", "{team1} y {team2} no se han enfrentado"),
+    ("not_started", "Aún no ha empezado"),
+    ("loading", "Cargando..."),
+    (
+        "picks_hidden",
+        "Los picks están ocultos mientras no se muestren los resultados",
+    ),
+    ("no_games", "Aún no se ha jugado ninguna partida"),
+    ("game_title", "Partida {}"),
+    ("patch", "parche {}"),
+    ("no_picks", "No hay picks disponibles"),
+    ("no_stats", "No hay estadísticas disponibles"),
+    ("loading_stats", "Cargando estadísticas..."),
+];
+
+const SWEDISH: &[(&str, &str)] = &[
+    ("schedule", "Schema"),
+    ("leagues", "Ligor"),
+    ("best_of", "Bäst av"),
+    ("play_all", "Alla matcher"),
+    ("completed", "Avslutad"),
+    ("in_progress", "Pågår"),
+    ("unstarted", "Ej startad"),
+    ("vs", "mot"),
+    ("recent", "Senaste"),
+    ("hidden_one", "1 match dold"),
+    ("hidden", "{} matcher dolda"),
+    ("today_one", "Idag: 1 match"),
+    ("today", "Idag: {} matcher"),
+    ("live_now", "{} live"),
+    ("next_at", "nästa kl. {}"),
+    ("next", "nästa {}"),
+    ("nothing_upcoming", "inget kommande"),
+    ("live", "Live"),
+    ("game", "omgång {}"),
+    ("rescheduled_one", "1 match flyttad"),
+    ("rescheduled", "{} matcher flyttade"),
+    ("added_one", "1 ny match"),
+    ("added", "{} nya matcher"),
+    ("removed_one", "1 match borttagen"),
+    ("removed", "{} matcher borttagna"),
+    ("details", "Detaljer"),
+    ("ends_around", "Slutar runt {time}, efter cirka {length}"),
+    (
+        "head_to_head_hidden",
+        "Inbördes möten döljs så länge resultaten inte visas",
+    ),
+    ("head_to_head_one", "Inbördes möten: {record} på 1 match"),
+    ("head_to_head", "Inbördes möten: {record} på {} matcher"),
+    ("never_played", "{team1} och {team2} har inte mötts"),
+    ("not_started", "Inte startad än"),
+    ("loading", "Laddar..."),
+    ("picks_hidden", "Picks döljs så länge resultaten inte visas"),
+    ("no_games", "Inga omgångar spelade än"),
+    ("game_title", "Omgång {}"),
+    ("patch", "patch {}"),
+    ("no_picks", "Inga picks tillgängliga"),
+    ("no_stats", "Ingen statistik tillgänglig"),
+    ("loading_stats", "Laddar statistik..."),
+];
+
+impl Strings {
+    // A bundled translation, with every key set
+    pub fn bundled(language: &str) -> Option<Strings> {
+        let translation = match language.to_lowercase().as_str() {
+            "en" | "english" => return Some(Strings::default()),
+            "de" | "german" => GERMAN,
+            "es" | "spanish" => SPANISH,
+            "sv" | "swedish" => SWEDISH,
+            _ => return None,
+        };
+        let mut strings = Strings::default();
+        for (key, text) in translation {
+            if let Some(string) = strings.get_mut(key) {
+                *string = text.to_string();
+            }
+        }
+        Some(strings)
+    }
+
+    // Returns `None` for unknown keys
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut String> {
+        self.entries()
+            .into_iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, string)| string)
+    }
+
+    // Every string with its key, destructured so that a new field has to be listed
+    fn entries(&mut self) -> Vec<(&'static str, &mut String)> {
+        let Strings {
+            schedule,
+            leagues,
+            best_of,
+            play_all,
+            completed,
+            in_progress,
+            unstarted,
+            vs,
+            recent,
+            hidden_one,
+            hidden,
+            today_one,
+            today,
+            live_now,
+            next_at,
+            next,
+            nothing_upcoming,
+            live,
+            game,
+            rescheduled_one,
+            rescheduled,
+            added_one,
+            added,
+            removed_one,
+            removed,
+            details,
+            ends_around,
+            head_to_head_hidden,
+            head_to_head_one,
+            head_to_head,
+            never_played,
+            not_started,
+            loading,
+            picks_hidden,
+            no_games,
+            game_title,
+            patch,
+            no_picks,
+            no_stats,
+            loading_stats,
+        } = self;
+        vec![
+            ("schedule", schedule),
+            ("leagues", leagues),
+            ("best_of", best_of),
+            ("play_all", play_all),
+            ("completed", completed),
+            ("in_progress", in_progress),
+            ("unstarted", unstarted),
+            ("vs", vs),
+            ("recent", recent),
+            ("hidden_one", hidden_one),
+            ("hidden", hidden),
+            ("today_one", today_one),
+            ("today", today),
+            ("live_now", live_now),
+            ("next_at", next_at),
+            ("next", next),
+            ("nothing_upcoming", nothing_upcoming),
+            ("live", live),
+            ("game", game),
+            ("rescheduled_one", rescheduled_one),
+            ("rescheduled", rescheduled),
+            ("added_one", added_one),
+            ("added", added),
+            ("removed_one", removed_one),
+            ("removed", removed),
+            ("details", details),
+            ("ends_around", ends_around),
+            ("head_to_head_hidden", head_to_head_hidden),
+            ("head_to_head_one", head_to_head_one),
+            ("head_to_head", head_to_head),
+            ("never_played", never_played),
+            ("not_started", not_started),
+            ("loading", loading),
+            ("picks_hidden", picks_hidden),
+            ("no_games", no_games),
+            ("game_title", game_title),
+            ("patch", patch),
+            ("no_picks", no_picks),
+            ("no_stats", no_stats),
+            ("loading_stats", loading_stats),
+        ]
+    }
+
+    // States the API added later are shown as they are named
+    pub fn state<'a>(&'a self, state: &'a MatchState) -> &'a str {
+        match state {
+            MatchState::Completed(_) => &self.completed,
+            MatchState::InProgress(_) => &self.in_progress,
+            MatchState::Unstarted(_) => &self.unstarted,
+            MatchState::Unknown(name) => name,
+        }
+    }
+
+    pub fn strategy<'a>(&'a self, strategy: &'a StratType) -> &'a str {
+        match strategy {
+            StratType::BestOf(_) => &self.best_of,
+            StratType::PlayAll(_) => &self.play_all,
+            StratType::Unknown(name) => name,
+        }
+    }
+
+    // Ex: "2 matches rescheduled, 1 new match"
+    pub fn changes(&self, changes: &ScheduleChanges) -> String {
+        [
+            (
+                changes.rescheduled,
+                &self.rescheduled_one,
+                &self.rescheduled,
+            ),
+            (changes.added, &self.added_one, &self.added),
+            (changes.removed, &self.removed_one, &self.removed),
+        ]
+        .into_iter()
+        .filter(|(count, _, _)| *count > 0)
+        .map(|(count, one, many)| Strings::count(count, one, many))
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+
+    // The string for one, or the one for more with `{}` replaced by the count, as languages
+    // differ in more than the noun
+    pub fn count(count: usize, one: &str, many: &str) -> String {
+        match count {
+            1 => one.to_string(),
+            n => many.replace("{}", &n.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_each_kind_of_change_with_its_count() {
+        let changes = ScheduleChanges {
+            rescheduled: 2,
+            added: 1,
+            removed: 0,
+        };
+        assert_eq!(
+            Strings::default().changes(&changes),
+            "2 matches rescheduled, 1 new match"
+        );
+        assert_eq!(
+            Strings::bundled("de").unwrap().changes(&changes),
+            "2 Spiele verschoben, 1 neues Spiel"
+        );
+    }
+
+    #[test]
+    fn bundled_languages_set_every_key() {
+        let mut keys: Vec<_> = Strings::default()
+            .entries()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        keys.sort();
+        for translation in [GERMAN, SPANISH, SWEDISH] {
+            let mut translated: Vec<_> = translation.iter().map(|(key, _)| *key).collect();
+            translated.sort();
+            assert_eq!(translated, keys);
+        }
+    }
+}
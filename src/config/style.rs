@@ -0,0 +1,345 @@
+// Styles and borders of the TUI, only parsed with it
+use ratatui::{
+    style::{Color, Modifier, Style, Stylize},
+    widgets::BorderType,
+};
+
+use super::{Config, Error};
+
+#[derive(Debug)]
+pub struct Styles {
+    pub border: Option<BorderType>,
+    // Pane borders that are not set use `border`
+    pub leagues_border: Option<Option<BorderType>>,
+    pub schedule_border: Option<Option<BorderType>>,
+    pub popup_border: Option<Option<BorderType>>,
+    pub default: Style,
+    pub highlight: Style,
+    pub selected: Style,
+    pub winner: Option<Style>,
+    pub loser: Option<Style>,
+    pub date: Option<Style>,
+    pub title: Option<Style>,
+    pub counts: Option<Style>,
+    pub separator: Option<Style>,
+    pub state: Option<Style>,
+    pub in_progress: Option<Style>,
+    // The game score in the state column of matches in progress
+    pub live_score: Option<Style>,
+    pub info: Option<Style>,
+    pub favorite: Option<Style>,
+    pub series: Option<Style>,
+    pub conflict: Option<Style>,
+}
+
+impl Default for Styles {
+    fn default() -> Self {
+        Styles {
+            border: Some(BorderType::Plain),
+            leagues_border: None,
+            schedule_border: None,
+            popup_border: None,
+            default: Style::default(),
+            highlight: Style::default().blue(),
+            selected: Style::default().red().bold(),
+            winner: Some(Style::default().green()),
+            loser: None,
+            date: None,
+            title: None,
+            counts: None,
+            separator: None,
+            state: None,
+            in_progress: None,
+            live_score: Some(Style::default().cyan().bold()),
+            info: None,
+            favorite: Some(Style::default().yellow()),
+            series: None,
+            conflict: Some(Style::default().magenta()),
+        }
+    }
+}
+
+// Element styles that are not set fall back to the style they were derived from
+impl Styles {
+    // Keeps only the modifiers, Ex: bold, for terminals without colors
+    pub fn monochrome(&mut self) {
+        self.map(|style| {
+            Style::new()
+                .add_modifier(style.add_modifier)
+                .remove_modifier(style.sub_modifier)
+        });
+    }
+
+    // Replaces the colors of every style with the closest of the 16 basic colors
+    pub fn basic_colors(&mut self) {
+        self.map(|style| Style {
+            fg: style.fg.map(nearest_basic_color),
+            bg: style.bg.map(nearest_basic_color),
+            ..style
+        });
+    }
+
+    fn map(&mut self, map: impl Fn(Style) -> Style) {
+        for style in [&mut self.default, &mut self.highlight, &mut self.selected] {
+            *style = map(*style);
+        }
+        for style in [
+            &mut self.winner,
+            &mut self.loser,
+            &mut self.date,
+            &mut self.title,
+            &mut self.counts,
+            &mut self.separator,
+            &mut self.state,
+            &mut self.in_progress,
+            &mut self.live_score,
+            &mut self.info,
+            &mut self.favorite,
+            &mut self.series,
+            &mut self.conflict,
+        ] {
+            *style = style.map(&map);
+        }
+    }
+
+    pub fn leagues_border(&self) -> Option<BorderType> {
+        self.leagues_border.unwrap_or(self.border)
+    }
+
+    pub fn schedule_border(&self) -> Option<BorderType> {
+        self.schedule_border.unwrap_or(self.border)
+    }
+
+    // Popups always have a border, so `none` draws a plain one
+    pub fn popup_border(&self) -> BorderType {
+        self.popup_border.unwrap_or(self.border).unwrap_or_default()
+    }
+
+    pub fn date(&self) -> Style {
+        self.date.unwrap_or(self.highlight.bg(Color::Reset))
+    }
+
+    pub fn title(&self) -> Style {
+        self.title.unwrap_or(self.highlight.bg(Color::Reset))
+    }
+
+    pub fn counts(&self) -> Style {
+        self.counts.unwrap_or(self.highlight)
+    }
+
+    pub fn separator(&self) -> Style {
+        self.separator.unwrap_or(self.default)
+    }
+}
+
+pub(super) fn parse_style_section(
+    config: &mut Config,
+    style: &[(String, String)],
+) -> Result<(), Error> {
+    for (raw_key, raw_style) in style {
+        let result = parse_style_key(config, raw_key, raw_style);
+        config.lenient(result)?;
+    }
+    Ok(())
+}
+
+fn parse_style_key(config: &mut Config, raw_key: &str, raw_style: &str) -> Result<(), Error> {
+    match raw_key {
+        "default" => config.style.default = parse_style(raw_style)?,
+        "highlight" => config.style.highlight = parse_style(raw_style)?,
+        "selected" => config.style.selected = parse_style(raw_style)?,
+        "winner" => config.style.winner = parse_optional_style(raw_style)?,
+        "loser" => config.style.loser = parse_optional_style(raw_style)?,
+        "date" => config.style.date = Some(parse_style(raw_style)?),
+        "title" => config.style.title = Some(parse_style(raw_style)?),
+        "counts" => config.style.counts = Some(parse_style(raw_style)?),
+        "separator" => config.style.separator = Some(parse_style(raw_style)?),
+        "state" => config.style.state = parse_optional_style(raw_style)?,
+        "in_progress" => config.style.in_progress = parse_optional_style(raw_style)?,
+        "live_score" => config.style.live_score = parse_optional_style(raw_style)?,
+        "info" => config.style.info = parse_optional_style(raw_style)?,
+        "favorite" => config.style.favorite = parse_optional_style(raw_style)?,
+        "series" => config.style.series = parse_optional_style(raw_style)?,
+        "conflict" => config.style.conflict = parse_optional_style(raw_style)?,
+        "border" => config.style.border = parse_border_type(raw_style)?,
+        "border.leagues" => config.style.leagues_border = Some(parse_border_type(raw_style)?),
+        "border.schedule" => config.style.schedule_border = Some(parse_border_type(raw_style)?),
+        "border.popup" => config.style.popup_border = Some(parse_border_type(raw_style)?),
+        _ => {
+            return Err(Error::UnknownKey(raw_key.to_string(), "style".to_string()));
+        }
+    };
+    Ok(())
+}
+
+fn parse_style(line: &str) -> Result<Style, Error> {
+    let line = line.to_lowercase();
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let split = words.iter().position(|w| *w == "on").unwrap_or(words.len());
+    let foreground = process_color_string(&words[..split].join(" "));
+    let background = process_color_string(&words[(split + 1).min(words.len())..].join(" "));
+
+    let mut style = Style::default();
+    if !foreground.0.is_empty() {
+        let fg = parse_color(&foreground.0)?;
+        style = style.fg(fg);
+    }
+    if !background.0.is_empty() {
+        let bg = parse_color(&background.0)?;
+        style = style.bg(bg);
+    }
+    style = style.add_modifier(foreground.1 | background.1);
+    Ok(style)
+}
+
+fn parse_optional_style(line: &str) -> Result<Option<Style>, Error> {
+    if line.to_lowercase() == "none" {
+        Ok(None)
+    } else {
+        Ok(Some(parse_style(line)?))
+    }
+}
+
+pub const MODIFIERS: &[(&str, Modifier)] = &[
+    ("bold", Modifier::BOLD),
+    ("dim", Modifier::DIM),
+    ("italic", Modifier::ITALIC),
+    ("underline", Modifier::UNDERLINED),
+    ("inverse", Modifier::REVERSED),
+    ("crossed", Modifier::CROSSED_OUT),
+];
+
+// Splits modifier words from the color name, Ex: `bold italic bright red`
+fn process_color_string(color_str: &str) -> (String, Modifier) {
+    let mut modifiers = Modifier::empty();
+    let mut color = Vec::new();
+
+    for word in color_str.split_whitespace() {
+        match MODIFIERS
+            .iter()
+            .find(|(name, _)| word.eq_ignore_ascii_case(name))
+        {
+            Some((_, modifier)) => modifiers |= *modifier,
+            None => color.push(word),
+        }
+    }
+
+    (color.join(" "), modifiers)
+}
+
+// The xterm defaults of the 16 basic colors, terminals without RGB colors often use others
+const BASIC_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+// Maps RGB colors, and the 256 color palette, to the closest basic color
+pub fn nearest_basic_color(color: Color) -> Color {
+    let (r, g, b) = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        // The 6x6x6 color cube
+        Color::Indexed(i @ 16..=231) => {
+            let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+            let i = i - 16;
+            (level(i / 36), level(i / 6 % 6), level(i % 6))
+        }
+        // The grayscale ramp
+        Color::Indexed(i @ 232..) => {
+            let level = 8 + (i - 232) * 10;
+            (level, level, level)
+        }
+        color => return color,
+    };
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(r, r2) + d(g, g2) + d(b, b2)
+    };
+    let index = (0..BASIC_COLORS.len())
+        .min_by_key(|i| distance(BASIC_COLORS[*i]))
+        .unwrap_or_default();
+    Color::Indexed(index as u8)
+}
+
+fn parse_color(s: &str) -> Result<Color, Error> {
+    if let Some(rgb) = parse_rgb(s) {
+        return Ok(Color::Rgb(rgb.0, rgb.1, rgb.2));
+    }
+    if let Some(index) = parse_indexed(s) {
+        return Ok(Color::Indexed(index));
+    }
+    match s {
+        "black" => Ok(Color::Indexed(0)),
+        "red" => Ok(Color::Indexed(1)),
+        "green" => Ok(Color::Indexed(2)),
+        "yellow" => Ok(Color::Indexed(3)),
+        "blue" => Ok(Color::Indexed(4)),
+        "magenta" => Ok(Color::Indexed(5)),
+        "cyan" => Ok(Color::Indexed(6)),
+        "gray" | "grey" => Ok(Color::Indexed(7)),
+        "bright black" | "dark gray" | "dark grey" => Ok(Color::Indexed(8)),
+        "bright red" => Ok(Color::Indexed(9)),
+        "bright green" => Ok(Color::Indexed(10)),
+        "bright yellow" => Ok(Color::Indexed(11)),
+        "bright blue" => Ok(Color::Indexed(12)),
+        "bright magenta" => Ok(Color::Indexed(13)),
+        "bright cyan" => Ok(Color::Indexed(14)),
+        "white" => Ok(Color::Indexed(15)),
+        // Extended names from the 256 color palette
+        "navy" => Ok(Color::Indexed(18)),
+        "teal" => Ok(Color::Indexed(30)),
+        "maroon" => Ok(Color::Indexed(88)),
+        "purple" => Ok(Color::Indexed(93)),
+        "olive" => Ok(Color::Indexed(100)),
+        "lime" => Ok(Color::Indexed(118)),
+        "brown" => Ok(Color::Indexed(130)),
+        "violet" => Ok(Color::Indexed(177)),
+        "gold" => Ok(Color::Indexed(178)),
+        "orange" => Ok(Color::Indexed(208)),
+        "pink" => Ok(Color::Indexed(218)),
+        "silver" => Ok(Color::Indexed(250)),
+        "reset" => Ok(Color::Reset),
+        _ => Err(Error::InvalidColor(s.to_string())),
+    }
+}
+
+// Parses indexed colors from the 256 color palette, Ex: `color208`
+fn parse_indexed(s: &str) -> Option<u8> {
+    s.strip_prefix("color")?.parse().ok()
+}
+
+fn parse_rgb(s: &str) -> Option<(u8, u8, u8)> {
+    if !s.starts_with('#') || s.len() != 7 {
+        return None;
+    }
+
+    let r = u8::from_str_radix(&s[1..3], 16).ok()?;
+    let g = u8::from_str_radix(&s[3..5], 16).ok()?;
+    let b = u8::from_str_radix(&s[5..7], 16).ok()?;
+
+    Some((r, g, b))
+}
+
+fn parse_border_type(line: &str) -> Result<Option<BorderType>, Error> {
+    match line.to_lowercase().as_str() {
+        "plain" => Ok(Some(BorderType::Plain)),
+        "rounded" => Ok(Some(BorderType::Rounded)),
+        "double" => Ok(Some(BorderType::Double)),
+        "thick" => Ok(Some(BorderType::Thick)),
+        "none" => Ok(None),
+        _ => Err(Error::InvalidBorder(line.to_string())),
+    }
+}
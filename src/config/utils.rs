@@ -1,10 +1,15 @@
 use std::path::PathBuf;
 
 use directories::ProjectDirs;
+#[cfg(feature = "tui")]
 use ratatui::{symbols::line, widgets::BorderType};
 
+use super::PROJECT_NAME;
+
 pub fn get_config_dir() -> PathBuf {
-    if let Some(proj_dirs) = project_directory() {
+    if let Some(dir) = env_dir("CONFIG_DIR") {
+        dir
+    } else if let Some(proj_dirs) = project_directory() {
         proj_dirs.config_local_dir().to_path_buf()
     } else {
         PathBuf::from(".").join(".config")
@@ -12,17 +17,34 @@ pub fn get_config_dir() -> PathBuf {
 }
 
 pub fn get_data_dir() -> PathBuf {
-    if let Some(proj_dirs) = project_directory() {
+    if let Some(dir) = env_dir("DATA_DIR") {
+        dir
+    } else if let Some(proj_dirs) = project_directory() {
         proj_dirs.data_local_dir().to_path_buf()
     } else {
         PathBuf::from(".").join(".config")
     }
 }
 
+// Each profile keeps its files in a directory of its own, Ex: `profiles/spoiler-free`
+pub fn profile_dir(dir: PathBuf, profile: Option<&str>) -> PathBuf {
+    match profile {
+        Some(name) => dir.join("profiles").join(name),
+        None => dir,
+    }
+}
+
+fn env_dir(name: &str) -> Option<PathBuf> {
+    std::env::var_os(format!("{}_{}", PROJECT_NAME.as_str(), name))
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
 fn project_directory() -> Option<ProjectDirs> {
     ProjectDirs::from("com", "johannesHHM", env!("CARGO_PKG_NAME"))
 }
 
+#[cfg(feature = "tui")]
 pub fn get_border_connections(border: Option<BorderType>) -> Option<line::Set> {
     match border {
         Some(BorderType::Plain) => Some(line::NORMAL),
@@ -1,18 +1,24 @@
 use color_eyre::eyre::OptionExt;
 use futures::{FutureExt, StreamExt};
 use ratatui::crossterm::event::Event as CrosstermEvent;
-use tokio::sync::mpsc::{self, UnboundedSender};
+use std::{collections::HashSet, time::Duration};
+use tokio::sync::{
+    mpsc::{self, UnboundedSender},
+    oneshot,
+};
+use tokio::time;
 use tracing::error;
 
-use crate::widgets;
+use crate::{command, models, net::livestats::PlayerStats, widgets::details::GameDetails};
 
 #[derive(Clone, Debug)]
 pub enum Event {
+    Tick,
     Crossterm(CrosstermEvent),
     App(AppEvent),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum AppEvent {
     Quit,
     Up,
@@ -20,31 +26,110 @@ pub enum AppEvent {
     Left,
     Right,
     Select,
+    Undo,
+    FocusNext,
+    FocusPrev,
     GotoToday,
+    GotoFavorite,
+    // Selects the next match in progress
+    GotoLive,
+    CycleDateRange,
+    CycleLeagueSort,
+    // Replaces the selected leagues with the leagues of a `[groups]` entry
+    ActivateGroup(String),
+    ToggleUpcomingLeagues,
+    ToggleFold,
+    // Opens the command line to set a reminder on the selected match
+    RemindMatch,
+    ShowDetails,
+    // The games of a match by its id, or why they could not be fetched
+    RecieveDetails(String, Result<Vec<GameDetails>, String>),
+    // The player stats of a game by its id
+    RecieveStats(String, Result<Vec<PlayerStats>, String>),
+    // Lists completed matches that are not marked as watched
+    ShowQueue,
+    // Lists the failed API requests of this session
+    ShowHealth,
+    ToggleWatched,
+    OpenLink,
+    WatchStream,
+    LaunchPlayer(String),
     ToggleSpoilResults,
     ToggleSpoilMatches,
     ReloadLeagues,
-    RecieveLeagues(Vec<widgets::leagues::League>),
+    RecieveLeagues(Vec<models::League>),
+    // Ids of the leagues whose cached schedule has a match yet to start
+    RecieveUpcoming(HashSet<String>),
     ReloadSchedule,
-    RecieveSchedule((String, Vec<widgets::events::Event>)),
+    RefreshSchedule,
+    RecieveSchedules(Vec<(String, Vec<models::Event>)>),
+    ExpireToasts,
+    CommandMode,
+    CommandPalette,
+    ToggleMouse,
+    Suspend,
+    // A command received through the command socket, answered once it ran
+    RemoteCommand(Box<command::Command>, Reply),
+    Error(String),
+    Warning(String),
 }
 
+// Where the result of a command from the command socket is sent, `Err` holds the error
+// the command reported
+#[derive(Clone, Debug)]
+pub struct Reply(pub UnboundedSender<Result<(), String>>);
+
+impl PartialEq for Reply {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.same_channel(&other.0)
+    }
+}
+
+// Whether the event task should stop reading the terminal, answered once it did
+type Pause = (bool, oneshot::Sender<()>);
+
 #[derive(Debug)]
 pub struct EventHandler {
     sender: mpsc::UnboundedSender<Event>,
     receiver: mpsc::UnboundedReceiver<Event>,
+    tick_rate: Option<Duration>,
+    // Set by `listen`
+    pause: Option<mpsc::UnboundedSender<Pause>>,
 }
 
 impl EventHandler {
-    pub fn new() -> Self {
+    pub fn new(tick_rate: Option<Duration>) -> Self {
         let (sender, receiver) = mpsc::unbounded_channel();
-        let actor = EventTask::new(sender.clone());
+        Self {
+            sender,
+            receiver,
+            tick_rate,
+            pause: None,
+        }
+    }
+
+    // Starts reading the terminal and ticking, not needed to only render into a buffer
+    pub fn listen(&mut self) {
+        let (pause, paused) = mpsc::unbounded_channel();
+        self.pause = Some(pause);
+        let actor = EventTask::new(self.sender.clone(), paused, self.tick_rate);
         tokio::spawn(async { actor.run().await });
-        Self { sender, receiver }
+    }
+
+    // Stops or resumes reading the terminal, so a program run in it gets every key.
+    // Returns once the terminal is no longer read
+    pub async fn pause_input(&self, paused: bool) {
+        let Some(pause) = &self.pause else {
+            return;
+        };
+        let (done, wait) = oneshot::channel();
+        if pause.send((paused, done)).is_ok() {
+            let _ = wait.await;
+        }
     }
 
     pub fn get_sender_clone(&self) -> UnboundedSender<Event> {
-        return self.sender.clone();
+        self.sender.clone()
     }
 
     pub async fn next(&mut self) -> color_eyre::Result<Event> {
@@ -63,17 +148,46 @@ impl EventHandler {
 
 struct EventTask {
     sender: mpsc::UnboundedSender<Event>,
+    paused: mpsc::UnboundedReceiver<Pause>,
+    tick_rate: Option<Duration>,
 }
 
 impl EventTask {
-    fn new(sender: mpsc::UnboundedSender<Event>) -> Self {
-        Self { sender }
+    fn new(
+        sender: mpsc::UnboundedSender<Event>,
+        paused: mpsc::UnboundedReceiver<Pause>,
+        tick_rate: Option<Duration>,
+    ) -> Self {
+        Self {
+            sender,
+            paused,
+            tick_rate,
+        }
     }
 
-    async fn run(self) -> color_eyre::Result<()> {
-        let mut reader = crossterm::event::EventStream::new();
+    async fn run(mut self) -> color_eyre::Result<()> {
+        // Dropped while paused, the stream reads the terminal from a thread of its own
+        let mut reader = Some(crossterm::event::EventStream::new());
+        let mut ticker = self.tick_rate.map(|period| {
+            let mut interval = time::interval_at(time::Instant::now() + period, period);
+            // A suspended terminal should not be flooded with ticks on resume
+            interval.set_missed_tick_behavior(time::MissedTickBehavior::Skip);
+            interval
+        });
         loop {
-            let crossterm_event = reader.next().fuse();
+            let crossterm_event = async {
+                match reader.as_mut() {
+                    Some(reader) => reader.next().await,
+                    None => std::future::pending().await,
+                }
+            }
+            .fuse();
+            let tick = async {
+                match ticker.as_mut() {
+                    Some(interval) => interval.tick().await,
+                    None => std::future::pending().await,
+                }
+            };
             tokio::select! {
               _ = self.sender.closed() => {
                 break;
@@ -81,6 +195,16 @@ impl EventTask {
               Some(Ok(evt)) = crossterm_event => {
                 self.send(Event::Crossterm(evt));
               }
+              _ = tick => {
+                self.send(Event::Tick);
+              }
+              Some((paused, done)) = self.paused.recv() => {
+                reader = match paused {
+                    true => None,
+                    false => Some(crossterm::event::EventStream::new()),
+                };
+                let _ = done.send(());
+              }
             };
         }
         Ok(())
@@ -0,0 +1,599 @@
+use std::fmt::{Display, Write};
+
+use chrono::{DateTime, Days, Duration, Local, NaiveDate, Offset, TimeZone, Utc};
+use serde::Serialize;
+
+use crate::config::{Config, MaskScope};
+use crate::models::{Event, MatchState, StratType};
+
+// Version of the JSON schema, bumped on breaking changes
+const SCHEMA_VERSION: u32 = 2;
+
+// Which hidden information an export may contain
+#[derive(Debug, Clone, Copy)]
+pub struct Spoilers<'a> {
+    pub results: bool,
+    pub matches: bool,
+    // Shown instead of what hidden matches hide, from `spoiler_mask`
+    pub mask: &'a str,
+    pub scope: MaskScope,
+}
+
+impl Spoilers<'_> {
+    // Only unplayed matches hide anything, and only while matches are not spoiled
+    fn hides(&self, event: &Event, scope: MaskScope) -> bool {
+        !self.matches && matches!(event.state, MatchState::Unstarted(_)) && self.scope >= scope
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Schedule<'a> {
+    version: u32,
+    matches: Vec<Match<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct Match<'a> {
+    id: &'a str,
+    league: &'a str,
+    league_slug: &'a str,
+    block: &'a str,
+    // Only the date when the time is hidden
+    start_time: String,
+    state: &'a str,
+    // `None` when hidden
+    format: Option<Format>,
+    teams: Vec<Team<'a>>,
+    winner: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+struct Format {
+    #[serde(rename = "type")]
+    format_type: &'static str,
+    count: u16,
+}
+
+#[derive(Debug, Serialize)]
+struct Team<'a> {
+    name: &'a str,
+    code: &'a str,
+    wins: Option<u16>,
+}
+
+// Keeps the events starting between `from` and `to` in local time, both inclusive
+pub fn filter_dates(
+    events: Vec<Event>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Vec<Event> {
+    events
+        .into_iter()
+        .filter(|e| {
+            let date = e.start_time.date_naive();
+            from.is_none_or(|from| date >= from) && to.is_none_or(|to| date <= to)
+        })
+        .collect()
+}
+
+pub fn to_json(events: &[Event], spoilers: Spoilers<'_>) -> serde_json::Result<String> {
+    let matches = events.iter().map(|e| to_match(e, spoilers)).collect();
+    serde_json::to_string_pretty(&Schedule {
+        version: SCHEMA_VERSION,
+        matches,
+    })
+}
+
+fn to_match<'a>(event: &'a Event, spoilers: Spoilers<'a>) -> Match<'a> {
+    let masked = spoilers.hides(event, MaskScope::Teams);
+    let row_masked = spoilers.hides(event, MaskScope::Row);
+    let result = event.result.as_ref().filter(|_| spoilers.results);
+    let wins = |i: usize| result.map(|r| if i == 0 { r.game_wins.0 } else { r.game_wins.1 });
+
+    let teams: Vec<Team> = event
+        .teams
+        .iter()
+        .enumerate()
+        .map(|(i, t)| Team {
+            name: if masked { spoilers.mask } else { &t.name },
+            code: if masked { spoilers.mask } else { &t.short },
+            wins: wins(i),
+        })
+        .collect();
+
+    let winner = match (&event.state, result) {
+        (MatchState::Completed(_), Some(r)) if r.game_wins.0 > r.game_wins.1 => {
+            Some(event.teams[0].short.as_str())
+        }
+        (MatchState::Completed(_), Some(r)) if r.game_wins.1 > r.game_wins.0 => {
+            Some(event.teams[1].short.as_str())
+        }
+        _ => None,
+    };
+
+    Match {
+        id: &event.match_id,
+        league: if row_masked {
+            spoilers.mask
+        } else {
+            &event.league_name
+        },
+        league_slug: if row_masked {
+            spoilers.mask
+        } else {
+            &event.league_slug
+        },
+        block: if row_masked {
+            spoilers.mask
+        } else {
+            &event.block_name
+        },
+        start_time: match spoilers.hides(event, MaskScope::Time) {
+            true => event.start_time.format("%Y-%m-%d").to_string(),
+            false => event.start_time.with_timezone(&Utc).to_rfc3339(),
+        },
+        state: match event.state {
+            MatchState::Completed(_) => "completed",
+            MatchState::InProgress(_) => "in_progress",
+            MatchState::Unstarted(_) => "unstarted",
+            MatchState::Unknown(_) => "unknown",
+        },
+        format: (!row_masked).then_some(Format {
+            format_type: match event.strategy.strat_type {
+                StratType::BestOf(_) => "best_of",
+                StratType::PlayAll(_) => "play_all",
+                StratType::Unknown(_) => "unknown",
+            },
+            count: event.strategy.count,
+        }),
+        teams,
+        winner,
+    }
+}
+
+// Writes an RFC 5545 calendar with one VEVENT per match, stamped with `now`
+pub fn to_ics(
+    events: &[Event],
+    spoilers: Spoilers<'_>,
+    config: &Config,
+    now: DateTime<Local>,
+) -> String {
+    calendar(events, spoilers, config, now, &[])
+}
+
+// A calendar to subscribe to, which tells calendar apps its name and how often to re-fetch it
+pub fn to_ics_feed(
+    events: &[Event],
+    spoilers: Spoilers<'_>,
+    config: &Config,
+    now: DateTime<Local>,
+    name: &str,
+    refresh: std::time::Duration,
+) -> String {
+    let refresh = format!("PT{}M", (refresh.as_secs() / 60).max(1));
+    calendar(
+        events,
+        spoilers,
+        config,
+        now,
+        &[
+            format!("X-WR-CALNAME:{}", escape(name)),
+            format!("NAME:{}", escape(name)),
+            format!("REFRESH-INTERVAL;VALUE=DURATION:{}", refresh),
+            format!("X-PUBLISHED-TTL:{}", refresh),
+        ],
+    )
+}
+
+fn calendar(
+    events: &[Event],
+    spoilers: Spoilers<'_>,
+    config: &Config,
+    now: DateTime<Local>,
+    properties: &[String],
+) -> String {
+    let mut out = String::new();
+    let now = format_ics_time(now.with_timezone(&Utc));
+    line(&mut out, "BEGIN:VCALENDAR");
+    line(&mut out, "VERSION:2.0");
+    line(&mut out, "PRODID:-//johannesHHM//lol-cal//EN");
+    line(&mut out, "CALSCALE:GREGORIAN");
+    for property in properties {
+        line(&mut out, property);
+    }
+    for event in events {
+        let exported = to_match(event, spoilers);
+        let teams = match (exported.teams.first(), exported.teams.get(1)) {
+            (Some(t0), Some(t1)) => match (t0.wins, t1.wins) {
+                (Some(w0), Some(w1)) => format!("{} {} - {} {}", t0.code, w0, w1, t1.code),
+                _ => format!("{} vs {}", t0.code, t1.code),
+            },
+            _ => "TBD".to_string(),
+        };
+        let start = event.start_time.with_timezone(&Utc);
+        // The API has no end times, without an estimate of the series a game takes an hour
+        let length = config
+            .series_duration(&event.strategy)
+            .and_then(|length| Duration::from_std(length).ok())
+            .unwrap_or(Duration::hours(event.strategy.count.max(1) as i64));
+        let end = start + length;
+
+        line(&mut out, "BEGIN:VEVENT");
+        // Match ids are stable, so re-importing updates events instead of duplicating them
+        line(&mut out, &format!("UID:{}@lol-cal", event.match_id));
+        line(&mut out, &format!("DTSTAMP:{}", now));
+        // Without a time the match is an all-day event on its local date
+        if spoilers.hides(event, MaskScope::Time) {
+            let date = event.start_time.date_naive();
+            let next = date + Days::new(1);
+            line(
+                &mut out,
+                &format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+            );
+            line(
+                &mut out,
+                &format!("DTEND;VALUE=DATE:{}", next.format("%Y%m%d")),
+            );
+        } else {
+            line(&mut out, &format!("DTSTART:{}", format_ics_time(start)));
+            line(&mut out, &format!("DTEND:{}", format_ics_time(end)));
+        }
+        line(
+            &mut out,
+            &format!(
+                "SUMMARY:{}",
+                escape(&format!("{}: {}", exported.league, teams))
+            ),
+        );
+        line(
+            &mut out,
+            &format!(
+                "DESCRIPTION:{}",
+                escape(&format!(
+                    "{} - {}\n{}",
+                    exported.league,
+                    exported.block,
+                    format_name(event, &exported, spoilers)
+                ))
+            ),
+        );
+        line(&mut out, &format!("CATEGORIES:{}", escape(exported.league)));
+        line(&mut out, "END:VEVENT");
+    }
+    line(&mut out, "END:VCALENDAR");
+    out
+}
+
+// Days included in a digest, starting today
+pub const DIGEST_DAYS: u64 = 7;
+
+// Keeps the events of the `DIGEST_DAYS` days starting on the day of `now`
+pub fn next_week(events: Vec<Event>, now: DateTime<Local>) -> Vec<Event> {
+    let today = now.date_naive();
+    filter_dates(
+        events,
+        Some(today),
+        Some(today + Days::new(DIGEST_DAYS - 1)),
+    )
+}
+
+// Markdown tables of matches, one per day, for pasting into chats. Days and times are those
+// of the time zone of `now`
+pub fn to_markdown<Tz: TimeZone>(
+    events: &[Event],
+    spoilers: Spoilers<'_>,
+    now: DateTime<Tz>,
+) -> String
+where
+    Tz::Offset: Display,
+{
+    let tz = now.timezone();
+    let mut out = format!(
+        "Matches of the next {} days, times are UTC{}\n\n",
+        DIGEST_DAYS,
+        now.format("%:z")
+    );
+    // A DST change within the week changes the offset, which is then named where it happens
+    let mut offset = now.offset().fix();
+    let mut day = None;
+    for event in events {
+        let start = event.start_time.with_timezone(&tz);
+        let date = start.date_naive();
+        let mut time = start.format("%H:%M").to_string();
+        if day != Some(date) {
+            if day.is_some() {
+                out.push('\n');
+            }
+            day = Some(date);
+            let mut heading = format!("**{}**", start.format("%A %d %B"));
+            if start.offset().fix() != offset {
+                offset = start.offset().fix();
+                heading.push_str(&format!(", times are UTC{}", start.format("%:z")));
+            }
+            let _ = writeln!(out, "{}", heading);
+            let _ = writeln!(out, "| Time | League | Match | Format |");
+            let _ = writeln!(out, "|------|--------|-------|--------|");
+        }
+        // Ex: "02:30 UTC+01:00", the hour repeated when DST ends is told apart by the offset
+        if spoilers.hides(event, MaskScope::Time) {
+            time = spoilers.mask.to_string();
+        } else if start.offset().fix() != offset {
+            offset = start.offset().fix();
+            time.push_str(&format!(" UTC{}", start.format("%:z")));
+        }
+        let exported = to_match(event, spoilers);
+        let teams = match (exported.teams.first(), exported.teams.get(1)) {
+            (Some(t0), Some(t1)) => match (t0.wins, t1.wins) {
+                (Some(w0), Some(w1)) => format!("{} {} - {} {}", t0.code, w0, w1, t1.code),
+                _ => format!("{} vs {}", t0.code, t1.code),
+            },
+            _ => "TBD".to_string(),
+        };
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} |",
+            markdown_escape(&time),
+            markdown_escape(exported.league),
+            markdown_escape(&teams),
+            markdown_escape(&format_name(event, &exported, spoilers))
+        );
+    }
+    if day.is_none() {
+        let _ = writeln!(out, "No matches in the next {} days", DIGEST_DAYS);
+    }
+    out
+}
+
+// Ex: "Best of 3", or the mask when the format is hidden
+fn format_name(event: &Event, exported: &Match, spoilers: Spoilers<'_>) -> String {
+    match exported.format {
+        Some(_) => format!(
+            "{} {}",
+            event.strategy.strat_type.get_string(),
+            event.strategy.count
+        ),
+        None => spoilers.mask.to_string(),
+    }
+}
+
+// Pipes would end the cell, and the rest could be read as formatting
+fn markdown_escape(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| match c {
+            '|' | '*' | '_' | '`' | '~' | '\\' => vec!['\\', c],
+            c => vec![c],
+        })
+        .collect()
+}
+
+fn format_ics_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+// Escapes TEXT values as described in RFC 5545 section 3.3.11
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// Content lines end in CRLF and are folded to at most 75 octets
+fn line(out: &mut String, content: &str) {
+    let mut width = 0;
+    for c in content.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset, LocalResult, NaiveDate, NaiveDateTime};
+
+    use super::*;
+    use crate::models::{Strategy, Team};
+
+    // Central European time of 2026, summer time from 29 March to 25 October at 01:00 UTC
+    #[derive(Debug, Clone, Copy)]
+    struct Cet;
+
+    impl Cet {
+        fn summer(utc: &NaiveDateTime) -> bool {
+            let at = |month, day| {
+                NaiveDate::from_ymd_opt(2026, month, day)
+                    .unwrap()
+                    .and_hms_opt(1, 0, 0)
+                    .unwrap()
+            };
+            (at(3, 29)..at(10, 25)).contains(utc)
+        }
+    }
+
+    impl TimeZone for Cet {
+        type Offset = FixedOffset;
+
+        fn from_offset(_: &FixedOffset) -> Self {
+            Cet
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            let offsets: Vec<_> = [1, 2]
+                .into_iter()
+                .map(|hours| FixedOffset::east_opt(hours * 3600).unwrap())
+                .filter(|offset| self.offset_from_utc_datetime(&(*local - *offset)) == *offset)
+                .collect();
+            match offsets[..] {
+                [offset] => LocalResult::Single(offset),
+                [winter, summer] => LocalResult::Ambiguous(summer, winter),
+                _ => LocalResult::None,
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            let hours = if Cet::summer(utc) { 2 } else { 1 };
+            FixedOffset::east_opt(hours * 3600).unwrap()
+        }
+    }
+
+    fn utc(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, month, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn event(start: DateTime<Utc>) -> Event {
+        let team = |code: &str| Team {
+            name: code.to_string(),
+            short: code.to_string(),
+        };
+        Event {
+            match_id: String::new(),
+            start_time: start.with_timezone(&chrono::Local),
+            league_name: "LEC".to_string(),
+            league_slug: "lec".to_string(),
+            block_name: "Week 1".to_string(),
+            strategy: Strategy {
+                strat_type: StratType::BestOf("Best of".to_string()),
+                count: 3,
+            },
+            state: MatchState::Unstarted("Unstarted".to_string()),
+            result: None,
+            teams: vec![team("G2"), team("FNC")],
+        }
+    }
+
+    fn digest(now: DateTime<Utc>, starts: &[DateTime<Utc>]) -> Vec<String> {
+        let events: Vec<_> = starts.iter().map(|start| event(*start)).collect();
+        let spoilers = Spoilers {
+            results: false,
+            matches: true,
+            mask: "???",
+            scope: MaskScope::Teams,
+        };
+        to_markdown(&events, spoilers, now.with_timezone(&Cet))
+            .lines()
+            // Without the blank lines and the table headers, which are the same for every day
+            .filter(|line| {
+                !line.is_empty() && !line.starts_with("| Time") && !line.starts_with("|--")
+            })
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn labels_the_day_summer_time_starts() {
+        let lines = digest(
+            utc(3, 27, 12, 0),
+            &[utc(3, 28, 17, 0), utc(3, 29, 0, 30), utc(3, 29, 10, 0)],
+        );
+        assert_eq!(
+            lines,
+            [
+                "Matches of the next 7 days, times are UTC+01:00",
+                "**Saturday 28 March**",
+                "| 18:00 | LEC | G2 vs FNC | Best of 3 |",
+                "**Sunday 29 March**",
+                "| 01:30 | LEC | G2 vs FNC | Best of 3 |",
+                "| 12:00 UTC+02:00 | LEC | G2 vs FNC | Best of 3 |",
+            ]
+        );
+    }
+
+    #[test]
+    fn labels_the_heading_when_a_day_starts_in_summer_time() {
+        let lines = digest(utc(3, 27, 12, 0), &[utc(3, 28, 17, 0), utc(3, 30, 16, 0)]);
+        assert_eq!(
+            lines,
+            [
+                "Matches of the next 7 days, times are UTC+01:00",
+                "**Saturday 28 March**",
+                "| 18:00 | LEC | G2 vs FNC | Best of 3 |",
+                "**Monday 30 March**, times are UTC+02:00",
+                "| 18:00 | LEC | G2 vs FNC | Best of 3 |",
+            ]
+        );
+    }
+
+    #[test]
+    fn tells_apart_the_hour_repeated_when_summer_time_ends() {
+        let lines = digest(
+            utc(10, 23, 12, 0),
+            &[utc(10, 25, 0, 30), utc(10, 25, 1, 30), utc(10, 26, 16, 0)],
+        );
+        assert_eq!(
+            lines,
+            [
+                "Matches of the next 7 days, times are UTC+02:00",
+                "**Sunday 25 October**",
+                "| 02:30 | LEC | G2 vs FNC | Best of 3 |",
+                "| 02:30 UTC+01:00 | LEC | G2 vs FNC | Best of 3 |",
+                "**Monday 26 October**",
+                "| 17:00 | LEC | G2 vs FNC | Best of 3 |",
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_by_the_local_day() {
+        // 23:30 UTC of the 24th is already the 25th in summer time
+        let lines = digest(
+            utc(10, 23, 12, 0),
+            &[utc(10, 24, 23, 30), utc(10, 25, 12, 0)],
+        );
+        assert_eq!(
+            lines,
+            [
+                "Matches of the next 7 days, times are UTC+02:00",
+                "**Sunday 25 October**",
+                "| 01:30 | LEC | G2 vs FNC | Best of 3 |",
+                "| 13:00 UTC+01:00 | LEC | G2 vs FNC | Best of 3 |",
+            ]
+        );
+    }
+
+    #[test]
+    fn masks_hidden_teams_with_the_spoiler_mask() {
+        let event = event(utc(10, 24, 16, 0));
+        let spoilers = Spoilers {
+            results: false,
+            matches: false,
+            mask: "---",
+            scope: MaskScope::Teams,
+        };
+        let exported = to_match(&event, spoilers);
+        let teams: Vec<_> = exported.teams.iter().map(|t| (t.name, t.code)).collect();
+        assert_eq!(teams, [("---", "---"), ("---", "---")]);
+    }
+
+    #[test]
+    fn masks_the_time_league_block_and_format_at_the_row_scope() {
+        let event = event(utc(10, 24, 12, 0));
+        let spoilers = Spoilers {
+            results: false,
+            matches: false,
+            mask: "---",
+            scope: MaskScope::Row,
+        };
+        let exported = to_match(&event, spoilers);
+        assert_eq!(exported.start_time, "2026-10-24");
+        assert_eq!(exported.block, "---");
+        assert_eq!(exported.league, "---");
+        assert!(exported.format.is_none());
+        let ics = to_ics(&[event], spoilers, &Config::default(), Local::now());
+        assert!(ics.contains("DTSTART;VALUE=DATE:20261024\r\n"));
+        assert!(ics.contains("DTEND;VALUE=DATE:20261025\r\n"));
+    }
+}
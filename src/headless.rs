@@ -0,0 +1,318 @@
+use std::{collections::HashMap, io::Write, path::Path, sync::Arc, time::Duration};
+
+use chrono::NaiveDate;
+use crossterm::style::{StyledContent, Stylize};
+use tokio::{
+    sync::mpsc::{self, UnboundedReceiver},
+    time,
+};
+use tracing::info;
+
+use crate::{
+    clock,
+    config::{Config, MaskScope, TeamOverride},
+    event::{AppEvent, Event},
+    export::{self, Spoilers},
+    models::{self, MatchState},
+    notify::Notifier,
+    resources::ResourceManager,
+};
+
+// Loads the schedules of the default leagues, sorted by start time. Warnings
+// the resource manager would toast in the TUI are printed to stderr instead.
+pub async fn load_events(config: &Config) -> color_eyre::Result<Vec<models::Event>> {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let resources = ResourceManager::from_config(config, sender);
+    let ids = resolve_leagues(config, &resources).await?;
+
+    let mut events = Vec::new();
+    for id in ids {
+        events.extend(resources.get_schedule(&id).await.unwrap_or_default());
+    }
+    events.sort_by_key(|e| e.start_time);
+    TeamOverride::apply(&config.team_overrides, &mut events);
+
+    report_messages(&mut receiver);
+    Ok(events)
+}
+
+// Ids of the default leagues
+async fn resolve_leagues(
+    config: &Config,
+    resources: &ResourceManager,
+) -> color_eyre::Result<Vec<String>> {
+    if config.default_leagues.is_empty() {
+        color_eyre::eyre::bail!("No leagues given, use --league or set default_leagues");
+    }
+    let leagues = resources.get_leagues().await.unwrap_or_default();
+    let mut ids = Vec::new();
+    for name in &config.default_leagues {
+        match leagues.iter().find(|l| l.matches_name(name)) {
+            Some(league) => ids.push(league.id.to_string()),
+            None => eprintln!("Could not find league '{}'", name),
+        }
+    }
+    Ok(ids)
+}
+
+pub fn report_messages(receiver: &mut UnboundedReceiver<Event>) {
+    while let Ok(event) = receiver.try_recv() {
+        if let Event::App(AppEvent::Error(msg) | AppEvent::Warning(msg)) = event {
+            eprintln!("{}", msg);
+        }
+    }
+}
+
+// Prints a line to stdout, a closed pipe, Ex: `lol-cal json | head`, is not an error
+fn print(line: &str) -> color_eyre::Result<()> {
+    match writeln!(std::io::stdout(), "{}", line) {
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+pub async fn next(config: &Config, count: usize) -> color_eyre::Result<()> {
+    let now = clock::now();
+    let events = load_events(config).await?;
+    for event in events
+        .iter()
+        .filter(|e| matches!(e.state, MatchState::InProgress(_)) || e.start_time >= now)
+        .take(count)
+    {
+        let (team0, team1) = match event.state {
+            MatchState::Unstarted(_) if !config.spoil_matches => {
+                (config.spoiler_mask.as_str(), config.spoiler_mask.as_str())
+            }
+            _ => (event.teams[0].short.as_str(), event.teams[1].short.as_str()),
+        };
+        let masked = !config.spoil_matches && matches!(event.state, MatchState::Unstarted(_));
+        let when = match event.state {
+            MatchState::InProgress(_) => "live".to_string(),
+            _ if masked && config.spoiler_mask_scope >= MaskScope::Time => {
+                event.start_time.format("%Y-%m-%d ").to_string() + &config.spoiler_mask
+            }
+            _ => event.start_time.format("%Y-%m-%d %H:%M").to_string(),
+        };
+        let format = match masked && config.spoiler_mask_scope == MaskScope::Row {
+            true => config.spoiler_mask.clone(),
+            false => format!(
+                "{} {}",
+                event.strategy.strat_type.get_string(),
+                event.strategy.count
+            ),
+        };
+        print(&format!(
+            "{:<16}  {:<12}  {:>5} vs {:<5}  {}",
+            when, event.league_name, team0, team1, format
+        ))?;
+    }
+    Ok(())
+}
+
+// Prints the schedule grouped by day, for when stdout is not a terminal or `--plain` is given
+pub async fn plain(config: &Config, color: bool) -> color_eyre::Result<()> {
+    let paint =
+        |text: String, style: fn(StyledContent<String>) -> StyledContent<String>| match color {
+            true => style(text.stylize()).to_string(),
+            false => text,
+        };
+    let mut day = None;
+    for event in load_events(config).await? {
+        let date = event.start_time.date_naive();
+        if day != Some(date) {
+            if day.is_some() {
+                print("")?;
+            }
+            day = Some(date);
+            print(&paint(
+                event.start_time.format("%A %d %B %Y").to_string(),
+                Stylize::bold,
+            ))?;
+        }
+        let (mut team0, mut team1) = match event.state {
+            MatchState::Unstarted(_) if !config.spoil_matches => (
+                format!("{:>5}", config.spoiler_mask),
+                format!("{:<5}", config.spoiler_mask),
+            ),
+            _ => (
+                format!("{:>5}", event.teams[0].short),
+                format!("{:<5}", event.teams[1].short),
+            ),
+        };
+        let score = match (&event.result, config.spoil_results) {
+            (Some(result), true) => {
+                let (wins0, wins1) = result.game_wins;
+                if matches!(event.state, MatchState::Completed(_)) {
+                    match wins0.cmp(&wins1) {
+                        std::cmp::Ordering::Greater => team0 = paint(team0, Stylize::green),
+                        std::cmp::Ordering::Less => team1 = paint(team1, Stylize::green),
+                        std::cmp::Ordering::Equal => {}
+                    }
+                }
+                format!("{}-{}", wins0, wins1)
+            }
+            _ => "   ".to_string(),
+        };
+        let state = match event.state {
+            MatchState::InProgress(_) => paint(event.state.get_string().to_string(), Stylize::red),
+            _ => event.state.get_string().to_string(),
+        };
+        let masked = !config.spoil_matches && matches!(event.state, MatchState::Unstarted(_));
+        let time = match masked && config.spoiler_mask_scope >= MaskScope::Time {
+            true => format!("{:<5}", config.spoiler_mask),
+            false => event.start_time.format("%H:%M").to_string(),
+        };
+        let format = match masked && config.spoiler_mask_scope == MaskScope::Row {
+            true => format!("{:<11}", config.spoiler_mask),
+            false => format!(
+                "{} {:<3}",
+                event.strategy.strat_type.get_string(),
+                event.strategy.count
+            ),
+        };
+        print(&format!(
+            "  {}  {:<12}  {:>5} vs {:<5}  {}  {}  {}",
+            time, event.league_name, team0, team1, score, format, state
+        ))?;
+    }
+    Ok(())
+}
+
+pub fn spoilers(config: &Config, spoilers: bool) -> Spoilers<'_> {
+    Spoilers {
+        results: spoilers || config.spoil_results,
+        matches: spoilers || config.spoil_matches,
+        mask: &config.spoiler_mask,
+        scope: config.spoiler_mask_scope,
+    }
+}
+
+pub async fn json(
+    config: &Config,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    spoilers: bool,
+) -> color_eyre::Result<()> {
+    let events = export::filter_dates(load_events(config).await?, from, to);
+    print(&export::to_json(&events, self::spoilers(config, spoilers))?)
+}
+
+pub async fn export_ics(
+    config: &Config,
+    out: Option<&Path>,
+    spoilers: bool,
+) -> color_eyre::Result<()> {
+    let events = load_events(config).await?;
+    let ics = export::to_ics(
+        &events,
+        self::spoilers(config, spoilers),
+        config,
+        clock::now(),
+    );
+    write_output(out, &ics, events.len())
+}
+
+pub async fn digest(config: &Config, out: Option<&Path>, spoilers: bool) -> color_eyre::Result<()> {
+    let events = export::next_week(load_events(config).await?, clock::now());
+    let markdown = export::to_markdown(&events, self::spoilers(config, spoilers), clock::now());
+    write_output(out, &markdown, events.len())
+}
+
+// Writes an export to `out`, or stdout if not given
+fn write_output(out: Option<&Path>, content: &str, count: usize) -> color_eyre::Result<()> {
+    match out {
+        Some(path) => {
+            std::fs::write(path, content)?;
+            eprintln!("Wrote {} matches to {}", count, path.display());
+            Ok(())
+        }
+        None => match std::io::stdout().write_all(content.as_bytes()) {
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+            result => Ok(result?),
+        },
+    }
+}
+
+// Re-fetches the schedules of the default leagues on an interval, and leagues with a match that
+// is live or due to start on the live refresh interval, sending notifications as they change
+pub async fn watch(config: Config, interval: Option<Duration>) -> color_eyre::Result<()> {
+    let config = Arc::new(config);
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let resources = ResourceManager::from_config(&config, sender);
+    let ids = resolve_leagues(&config, &resources).await?;
+    if ids.is_empty() {
+        color_eyre::eyre::bail!("None of the given leagues were found");
+    }
+    if !config.notifications.enabled && config.hooks.is_empty() {
+        eprintln!("Notifications are disabled and no hooks are set, only keeping the cache fresh");
+    }
+
+    let ticker = |period: Duration| {
+        let mut interval = time::interval_at(time::Instant::now() + period, period);
+        interval.set_missed_tick_behavior(time::MissedTickBehavior::Skip);
+        interval
+    };
+    let interval = interval
+        .or(config.refresh_interval)
+        .unwrap_or(Duration::from_secs(5 * 60));
+    let mut refresh = ticker(interval);
+    let mut live_refresh = config.live_refresh_interval.map(ticker);
+    let mut check = ticker(config.tick_rate.unwrap_or(Duration::from_secs(1)));
+    info!(
+        "Watching {} leagues, refreshing every {}s",
+        ids.len(),
+        interval.as_secs()
+    );
+
+    let mut notifier = Notifier::new(config.clone());
+    let mut schedules: HashMap<String, Vec<models::Event>> = HashMap::new();
+    // The first load may use the cache, later ones always fetch
+    for id in &ids {
+        if let Some(mut events) = resources.get_schedule(id).await {
+            TeamOverride::apply(&config.team_overrides, &mut events);
+            schedules.insert(id.to_string(), events);
+        }
+    }
+    report_messages(&mut receiver);
+
+    loop {
+        let live_tick = async {
+            match live_refresh.as_mut() {
+                Some(interval) => interval.tick().await,
+                None => std::future::pending().await,
+            }
+        };
+        let stale: Vec<String> = tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            _ = refresh.tick() => ids.clone(),
+            _ = live_tick => {
+                let now = clock::now();
+                schedules
+                    .iter()
+                    .filter(|(_, events)| {
+                        events.iter().any(|e| match e.state {
+                            MatchState::InProgress(_) => true,
+                            MatchState::Unstarted(_) => e.start_time <= now,
+                            _ => false,
+                        })
+                    })
+                    .map(|(id, _)| id.to_string())
+                    .collect()
+            }
+            _ = check.tick() => Vec::new(),
+        };
+        for id in stale {
+            if let Some(mut events) = resources.fetch_schedule(&id).await {
+                TeamOverride::apply(&config.team_overrides, &mut events);
+                schedules.insert(id, events);
+            }
+        }
+        report_messages(&mut receiver);
+
+        let mut events: Vec<&models::Event> = schedules.values().flatten().collect();
+        events.sort_by_key(|e| e.start_time);
+        notifier.check(&events);
+    }
+    info!("Stopped watching");
+    Ok(())
+}
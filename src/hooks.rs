@@ -0,0 +1,94 @@
+use tokio::process::Command;
+use tracing::{error, info, warn};
+
+use crate::{config::PROJECT_NAME, models::Event};
+
+// Environment variables describing a match, prefixed with `LOL_CAL_`
+pub fn match_env(event: &Event, kind: &str) -> Vec<(String, String)> {
+    let team = |i: usize| event.teams.get(i);
+    let mut env = vec![
+        ("EVENT", kind.to_string()),
+        ("MATCH_ID", event.match_id.to_string()),
+        ("LEAGUE", event.league_name.to_string()),
+        ("LEAGUE_SLUG", event.league_slug.to_string()),
+        ("BLOCK", event.block_name.to_string()),
+        ("START_TIME", event.start_time.to_rfc3339()),
+        (
+            "FORMAT",
+            format!(
+                "{} {}",
+                event.strategy.strat_type.get_string(),
+                event.strategy.count
+            ),
+        ),
+        (
+            "TEAM1",
+            team(0).map(|t| t.short.to_string()).unwrap_or_default(),
+        ),
+        (
+            "TEAM1_NAME",
+            team(0).map(|t| t.name.to_string()).unwrap_or_default(),
+        ),
+        (
+            "TEAM2",
+            team(1).map(|t| t.short.to_string()).unwrap_or_default(),
+        ),
+        (
+            "TEAM2_NAME",
+            team(1).map(|t| t.name.to_string()).unwrap_or_default(),
+        ),
+    ];
+    if !event.league_slug.is_empty() {
+        env.push((
+            "STREAM_URL",
+            format!("https://lolesports.com/live/{}", event.league_slug),
+        ));
+    }
+    env.into_iter()
+        .map(|(key, value)| (format!("{}_{}", PROJECT_NAME.as_str(), key), value))
+        .collect()
+}
+
+// `command` run through the shell, so it may quote its arguments
+pub fn shell(command: &str) -> Command {
+    #[cfg(unix)]
+    let (shell, flag) = ("sh", "-c");
+    #[cfg(windows)]
+    let (shell, flag) = ("cmd", "/C");
+    let mut process = Command::new(shell);
+    process.arg(flag).arg(command);
+    process
+}
+
+// `arg` as a single argument of a `shell` command
+pub fn quote(arg: &str) -> String {
+    #[cfg(unix)]
+    return format!("'{}'", arg.replace('\'', "'\\''"));
+    #[cfg(windows)]
+    return format!("\"{}\"", arg.replace('"', "\"\""));
+}
+
+// Runs `command` through the shell without waiting for it, logging how it exits
+pub fn run(command: &str, env: Vec<(String, String)>) {
+    info!("Running hook '{}'", command);
+    let mut process = shell(command);
+    process
+        .envs(env)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+
+    let command = command.to_string();
+    match process.spawn() {
+        Ok(mut child) => {
+            tokio::spawn(async move {
+                match child.wait().await {
+                    Ok(status) if status.success() => {}
+                    Ok(status) => warn!("Hook '{}' exited with {}", command, status),
+                    Err(e) => error!("Failed to wait for hook '{}': {}", command, e),
+                }
+            });
+        }
+        Err(e) => error!("Failed to run hook '{}': {}", command, e),
+    }
+}
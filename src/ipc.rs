@@ -0,0 +1,114 @@
+use std::path::Path;
+
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::event::Event;
+
+// A socket accepting lines in the command mode syntax, Ex: `goto today` or `league add LCK`,
+// answering each with `ok` or `error: MESSAGE` once it ran. Commands run as if entered in
+// command mode.
+#[cfg(unix)]
+pub fn listen(path: &Path, sender: UnboundedSender<Event>) -> std::io::Result<()> {
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::UnixListener,
+    };
+    use tracing::{info, warn};
+
+    use crate::{
+        command,
+        event::{AppEvent, Reply},
+    };
+
+    // A socket left behind by an instance that did not exit cleanly can be replaced
+    if path.exists() {
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AddrInUse,
+                format!("{} is used by another instance", path.display()),
+            ));
+        }
+        std::fs::remove_file(path)?;
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    info!("Listening for commands on {}", path.display());
+
+    tokio::spawn(async move {
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    warn!("Failed to accept socket connection: {:?}", e);
+                    continue;
+                }
+            };
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                let (reader, mut writer) = stream.into_split();
+                let mut lines = BufReader::new(reader).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    info!("Received command '{}'", line);
+                    let reply = match command::parse(&line) {
+                        Ok(command) => {
+                            let (reply, mut result) = tokio::sync::mpsc::unbounded_channel();
+                            let event = AppEvent::RemoteCommand(Box::new(command), Reply(reply));
+                            let _ = sender.send(Event::App(event));
+                            match result.recv().await {
+                                Some(Ok(())) => "ok\n".to_string(),
+                                Some(Err(e)) => format!("error: {}\n", e),
+                                None => {
+                                    "error: App stopped before running the command\n".to_string()
+                                }
+                            }
+                        }
+                        Err(e) => format!("error: {}\n", e),
+                    };
+                    if writer.write_all(reply.as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn listen(_path: &Path, _sender: UnboundedSender<Event>) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "command sockets are only supported on Unix",
+    ))
+}
+
+// Sends a command to a running instance, returning its reply
+#[cfg(unix)]
+pub async fn send(path: &Path, command: &str) -> std::io::Result<String> {
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::UnixStream,
+    };
+
+    let mut stream = BufReader::new(UnixStream::connect(path).await?);
+    stream
+        .get_mut()
+        .write_all(format!("{}\n", command.trim()).as_bytes())
+        .await?;
+    let mut reply = String::new();
+    stream.read_line(&mut reply).await?;
+    Ok(reply.trim_end().to_string())
+}
+
+#[cfg(not(unix))]
+pub async fn send(_path: &Path, _command: &str) -> std::io::Result<String> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "command sockets are only supported on Unix",
+    ))
+}
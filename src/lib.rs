@@ -0,0 +1,31 @@
+// The lolesports client, the models, the cache layer and the config parser build without the
+// TUI, disable the default `tui` feature to use them without ratatui
+pub mod clock;
+pub mod config;
+pub mod export;
+pub mod models;
+pub mod net;
+pub mod resources;
+
+#[cfg(feature = "tui")]
+pub mod app;
+#[cfg(feature = "tui")]
+pub mod cli;
+#[cfg(feature = "tui")]
+pub mod command;
+#[cfg(feature = "tui")]
+pub mod event;
+#[cfg(feature = "tui")]
+pub mod headless;
+#[cfg(feature = "tui")]
+pub mod hooks;
+#[cfg(feature = "tui")]
+pub mod ipc;
+#[cfg(feature = "tui")]
+pub mod logging;
+#[cfg(feature = "tui")]
+pub mod notify;
+#[cfg(feature = "tui")]
+pub mod serve;
+#[cfg(feature = "tui")]
+pub mod widgets;
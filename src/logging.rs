@@ -2,25 +2,37 @@ use color_eyre::Result;
 use tracing_error::ErrorLayer;
 use tracing_subscriber::{EnvFilter, fmt, prelude::*};
 
-use crate::config;
+use crate::config::{self, Config};
 
 lazy_static::lazy_static! {
     pub static ref LOG_ENV: String = format!("{}_LOG_LEVEL", config::PROJECT_NAME.clone());
     pub static ref LOG_FILE: String = format!("{}.log", env!("CARGO_PKG_NAME"));
 }
 
-pub fn init() -> Result<()> {
-    let directory = config::utils::get_data_dir();
-    std::fs::create_dir_all(directory.clone())?;
-    let log_path = directory.join(LOG_FILE.clone());
+pub fn init(config: &Config, filter: Option<&str>) -> Result<()> {
+    // A relative `log_file` is placed in the data directory
+    let log_path = match &config.log_file {
+        Some(path) => config.data_dir.join(path),
+        None => config.data_dir.join(LOG_FILE.clone()),
+    };
+    if let Some(parent) = log_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
     let log_file = std::fs::File::create(log_path)?;
     let env_filter = EnvFilter::builder().with_default_directive(tracing::Level::INFO.into());
-    // If the `RUST_LOG` environment variable is set, use that as the default, otherwise use the
-    // value of the `LOG_ENV` environment variable. If the `LOG_ENV` environment variable contains
-    // errors, then this will return an error.
-    let env_filter = env_filter
-        .try_from_env()
-        .or_else(|_| env_filter.with_env_var(LOG_ENV.clone()).from_env())?;
+    // The `--log-level` argument wins, then the `RUST_LOG` environment variable, then the value
+    // of the `LOG_ENV` environment variable, and lastly the `log_level` setting. If the
+    // `LOG_ENV` environment variable contains errors, then this will return an error.
+    let env_filter = match filter {
+        Some(filter) => env_filter.parse(filter)?,
+        None => match env_filter.try_from_env() {
+            Ok(filter) => filter,
+            Err(_) if std::env::var(LOG_ENV.as_str()).is_ok() => {
+                env_filter.with_env_var(LOG_ENV.clone()).from_env()?
+            }
+            Err(_) => env_filter.parse(config.log_level.as_deref().unwrap_or_default())?,
+        },
+    };
     let file_subscriber = fmt::layer()
         .with_file(true)
         .with_line_number(true)
@@ -1,23 +1,102 @@
-use crate::app::App;
-use tracing::*;
+use std::io::IsTerminal;
+
+use clap::Parser;
 
-pub mod app;
-pub mod config;
-pub mod event;
-pub mod logging;
-pub mod net;
-pub mod resources;
-pub mod widgets;
+use lol_cal::app::App;
+use lol_cal::cli::{Cli, Command};
+use lol_cal::{clock, config, headless, ipc, serve};
+use tracing::*;
 
 #[tokio::main]
 async fn main() -> color_eyre::Result<()> {
-    tui_main().await
+    let cli = Cli::parse();
+    if let Some(time) = cli.fake_now {
+        clock::start_at(time);
+    }
+    if cli.init_config {
+        return init_config(&cli);
+    }
+    if cli.check_config {
+        return check_config(&cli);
+    }
+    match cli.command {
+        Some(ref command) => headless_main(&cli, Some(command)).await,
+        None if cli.plain || !std::io::stdout().is_terminal() => headless_main(&cli, None).await,
+        None => tui_main(cli).await,
+    }
+}
+
+// Without a command the schedule is printed as plain text
+async fn headless_main(cli: &Cli, command: Option<&Command>) -> color_eyre::Result<()> {
+    color_eyre::install()?;
+    // Clap does not check conflicts with global arguments
+    if cli.no_spoilers && command.is_some_and(Command::spoilers) {
+        color_eyre::eyre::bail!("--spoilers can not be used with --no-spoilers");
+    }
+    let mut config = config::Config::new(cli.config.as_deref(), cli.profile.as_deref())?;
+    cli.apply(&mut config);
+    lol_cal::logging::init(&config, cli.log_level.as_deref())?;
+    let Some(command) = command else {
+        let color = config.use_color(std::io::stdout().is_terminal());
+        return headless::plain(&config, color).await;
+    };
+    match command {
+        Command::Next { count } => headless::next(&config, *count).await,
+        Command::Json { from, to, spoilers } => {
+            headless::json(&config, *from, *to, *spoilers).await
+        }
+        Command::ExportIcs { out, spoilers } => {
+            headless::export_ics(&config, out.as_deref(), *spoilers).await
+        }
+        Command::Digest { out, spoilers } => {
+            headless::digest(&config, out.as_deref(), *spoilers).await
+        }
+        Command::Send { command } => {
+            let Some(path) = config.socket_path() else {
+                color_eyre::eyre::bail!("No socket set, set the socket setting");
+            };
+            let reply = ipc::send(&path, &command.join(" ")).await?;
+            println!("{}", reply);
+            match reply.starts_with("error") {
+                true => std::process::exit(1),
+                false => Ok(()),
+            }
+        }
+        Command::Watch { interval } => headless::watch(config, *interval).await,
+        Command::Serve {
+            bind,
+            port,
+            interval,
+            spoilers,
+        } => serve::serve(config, *bind, *port, *interval, *spoilers).await,
+    }
+}
+
+fn init_config(cli: &Cli) -> color_eyre::Result<()> {
+    let path = cli
+        .config
+        .clone()
+        .unwrap_or_else(|| config::Config::default_path(cli.profile.as_deref()));
+    config::Config::write_default(&path)?;
+    println!("Wrote default config to {}", path.display());
+    Ok(())
+}
+
+fn check_config(cli: &Cli) -> color_eyre::Result<()> {
+    config::Config::check(cli.config.as_deref(), cli.profile.as_deref())?;
+    println!("Config is valid");
+    Ok(())
 }
 
-async fn tui_main() -> color_eyre::Result<()> {
+async fn tui_main(cli: Cli) -> color_eyre::Result<()> {
     color_eyre::install()?;
-    crate::logging::init()?;
-    let mut app = App::new()?;
+    let mut config = config::Config::new(cli.config.as_deref(), cli.profile.as_deref())?;
+    cli.apply(&mut config);
+    if !config.use_color(true) {
+        config.style.monochrome();
+    }
+    lol_cal::logging::init(&config, cli.log_level.as_deref())?;
+    let mut app = App::new(config);
     app.init();
 
     info!("{:?}", app.config);
@@ -0,0 +1,190 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Deserializer, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StratType {
+    BestOf(String),
+    PlayAll(String),
+    Unknown(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Strategy {
+    pub strat_type: StratType,
+    pub count: u16,
+}
+
+impl StratType {
+    pub fn get_string(&self) -> &str {
+        match self {
+            StratType::BestOf(s) => s,
+            StratType::PlayAll(s) => s,
+            StratType::Unknown(s) => s,
+        }
+    }
+}
+
+impl From<String> for StratType {
+    fn from(name: String) -> Self {
+        match name.as_str() {
+            "bestOf" => StratType::BestOf("Best of".to_string()),
+            "playAll" => StratType::PlayAll("Play all".to_string()),
+            _ => StratType::Unknown(name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MatchState {
+    Completed(String),
+    InProgress(String),
+    Unstarted(String),
+    Unknown(String),
+}
+
+impl MatchState {
+    pub fn get_string(&self) -> &str {
+        match self {
+            MatchState::Completed(s) => s,
+            MatchState::InProgress(s) => s,
+            MatchState::Unstarted(s) => s,
+            MatchState::Unknown(s) => s,
+        }
+    }
+}
+
+impl From<String> for MatchState {
+    fn from(name: String) -> Self {
+        match name.as_str() {
+            "completed" => MatchState::Completed("Completed".to_string()),
+            "inProgress" => MatchState::InProgress("In progress".to_string()),
+            "unstarted" => MatchState::Unstarted("Unstarted".to_string()),
+            _ => MatchState::Unknown(name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Team {
+    pub name: String,
+    pub short: String,
+}
+
+impl Team {
+    pub fn tbd() -> Self {
+        Self {
+            name: "TBD".to_string(),
+            short: "TBD".to_string(),
+        }
+    }
+}
+
+// Matches are shown with two teams, missing teams are shown as TBD
+pub fn two_teams(mut teams: Vec<Team>) -> Vec<Team> {
+    while teams.len() < 2 {
+        teams.push(Team::tbd());
+    }
+    teams
+}
+
+fn deserialize_teams<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Team>, D::Error> {
+    Vec::deserialize(deserializer).map(two_teams)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchResult {
+    pub game_wins: (u16, u16),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    #[serde(default)]
+    pub match_id: String,
+    pub start_time: DateTime<Local>,
+    pub league_name: String,
+    #[serde(default)]
+    pub league_slug: String,
+    pub block_name: String,
+    pub strategy: Strategy,
+    pub state: MatchState,
+    pub result: Option<MatchResult>,
+    // Always at least two teams, so `teams[0]` and `teams[1]` can be indexed
+    #[serde(deserialize_with = "deserialize_teams")]
+    pub teams: Vec<Team>,
+}
+
+impl Event {
+    // Matches of old caches may still be unstarted, so the start time has to be ahead too
+    pub fn is_upcoming(&self, now: DateTime<Local>) -> bool {
+        matches!(self.state, MatchState::Unstarted(_)) && self.start_time > now
+    }
+
+    // The lolesports page of the live stream or the VOD, used for hyperlinks
+    pub fn link_url(&self) -> Option<String> {
+        match self.state {
+            MatchState::InProgress(_) if !self.league_slug.is_empty() => {
+                Some(format!("https://lolesports.com/live/{}", self.league_slug))
+            }
+            MatchState::Completed(_) if !self.match_id.is_empty() => {
+                Some(format!("https://lolesports.com/vod/{}/1", self.match_id))
+            }
+            _ => None,
+        }
+    }
+}
+
+// What a refresh changed in the schedule of a league, matched by match id
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduleChanges {
+    pub rescheduled: usize,
+    pub added: usize,
+    // Only upcoming matches, past ones drop out of what the API returns
+    pub removed: usize,
+}
+
+impl ScheduleChanges {
+    pub fn between(old: &[&Event], new: &[Event], now: DateTime<Local>) -> Self {
+        let mut changes = Self::default();
+        for event in new.iter().filter(|e| !e.match_id.is_empty()) {
+            match old.iter().find(|e| e.match_id == event.match_id) {
+                Some(previous) if previous.start_time != event.start_time => {
+                    changes.rescheduled += 1
+                }
+                Some(_) => {}
+                None => changes.added += 1,
+            }
+        }
+        changes.removed = old
+            .iter()
+            .filter(|e| e.is_upcoming(now) && !e.match_id.is_empty())
+            .filter(|e| !new.iter().any(|n| n.match_id == e.match_id))
+            .count();
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct League {
+    pub name: String,
+    #[serde(default)]
+    pub slug: String,
+    pub region: String,
+    pub id: String,
+    // Lower comes first, as ordered on lolesports
+    #[serde(default)]
+    pub priority: i64,
+    pub selected: bool,
+}
+
+impl League {
+    // Matches against the name, slug or id of a league, ignoring case
+    pub fn matches_name(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self.slug.eq_ignore_ascii_case(name)
+            || self.id.eq_ignore_ascii_case(name)
+    }
+}
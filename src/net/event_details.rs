@@ -0,0 +1,137 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use reqwest::Client;
+
+use crate::net::*;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Root {
+    data: Data,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Data {
+    event: EventDetails,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventDetails {
+    pub id: String,
+    #[serde(rename = "match", default)]
+    pub match_field: Match,
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    pub streams: Vec<Media>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Match {
+    #[serde(default)]
+    pub teams: Vec<MatchTeam>,
+    #[serde(default)]
+    pub games: Vec<Game>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchTeam {
+    pub id: String,
+    #[serde(default)]
+    pub code: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Game {
+    #[serde(default)]
+    pub id: String,
+    pub number: i64,
+    // "completed", "inProgress", "unstarted" or "unneeded"
+    #[serde(default)]
+    pub state: String,
+    #[serde(default)]
+    pub vods: Vec<Media>,
+}
+
+impl Match {
+    // The code of a team by its id, which is how livestats names the teams
+    pub fn team_code(&self, id: &str) -> Option<&str> {
+        self.teams
+            .iter()
+            .find(|t| t.id == id)
+            .map(|t| t.code.as_str())
+    }
+}
+
+// A stream or a VOD, `parameter` is the provider specific id or channel
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Media {
+    pub parameter: String,
+    pub locale: String,
+    pub provider: String,
+}
+
+impl Media {
+    pub fn url(&self) -> String {
+        match self.provider.as_str() {
+            "youtube" => format!("https://www.youtube.com/watch?v={}", self.parameter),
+            "twitch" => format!("https://www.twitch.tv/{}", self.parameter),
+            "afreecatv" => format!("https://play.afreecatv.com/{}", self.parameter),
+            _ if self.parameter.starts_with("http") => self.parameter.to_string(),
+            _ => format!("https://lolesports.com/vod/{}", self.parameter),
+        }
+    }
+}
+
+impl EventDetails {
+    // Prefers media in `locale`, then english, then whatever comes first
+    fn pick<'a>(media: &'a [Media], locale: &str) -> Option<&'a Media> {
+        media
+            .iter()
+            .find(|m| m.locale.eq_ignore_ascii_case(locale))
+            .or_else(|| media.iter().find(|m| m.locale.starts_with("en")))
+            .or_else(|| media.first())
+    }
+
+    pub fn stream(&self, locale: &str) -> Option<&Media> {
+        Self::pick(&self.streams, locale)
+    }
+
+    // The VOD of the first game, which for most matches is where the broadcast starts
+    pub fn vod(&self, locale: &str) -> Option<&Media> {
+        self.match_field
+            .games
+            .iter()
+            .filter(|g| !g.vods.is_empty())
+            .min_by_key(|g| g.number)
+            .and_then(|g| Self::pick(&g.vods, locale))
+    }
+}
+
+fn deserialize_null_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+const EVENT_DETAILS_URL: &str =
+    "https://esports-api.lolesports.com/persisted/gw/getEventDetails?hl=en-US&id=";
+
+pub async fn fetch_event_details(
+    client: &Client,
+    source: &Source,
+    match_id: &str,
+) -> Result<EventDetails, Error> {
+    let url = EVENT_DETAILS_URL.to_owned() + match_id;
+    let fixture = format!("event-details-{}.json", match_id);
+    let body = get(client, source, &url, &fixture).await?;
+    let api_response: Root = parse(&body)?;
+    Ok(api_response.data.event)
+}
@@ -31,17 +31,8 @@ pub struct League {
 
 const LEAGUES_URL: &str = "https://esports-api.lolesports.com/persisted/gw/getLeagues?hl=en-US";
 
-pub async fn fetch_leagues(client: &Client) -> Result<Vec<League>, Error> {
-    let response = client
-        .get(LEAGUES_URL)
-        .header(X_API_KEY_NAME, X_API_KEY_VALUE)
-        .send()
-        .await?;
-
-    if response.status().is_success() {
-        let api_response: Root = response.json().await?;
-        return Ok(api_response.data.leagues);
-    } else {
-        return Err(Error::Request(response.status()));
-    }
+pub async fn fetch_leagues(client: &Client, source: &Source) -> Result<Vec<League>, Error> {
+    let body = get(client, source, LEAGUES_URL, "leagues.json").await?;
+    let api_response: Root = parse(&body)?;
+    Ok(api_response.data.leagues)
 }
@@ -0,0 +1,122 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use reqwest::Client;
+
+use crate::net::*;
+
+// The first frames of a game, which carry the patch and who plays which champion
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Window {
+    #[serde(default)]
+    pub game_metadata: GameMetadata,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameMetadata {
+    #[serde(default)]
+    pub patch_version: String,
+    #[serde(default)]
+    pub blue_team_metadata: TeamMetadata,
+    #[serde(default)]
+    pub red_team_metadata: TeamMetadata,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamMetadata {
+    #[serde(default)]
+    pub esports_team_id: String,
+    #[serde(default)]
+    pub participant_metadata: Vec<Participant>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Participant {
+    pub participant_id: u8,
+    #[serde(default)]
+    pub summoner_name: String,
+    #[serde(default)]
+    pub champion_id: String,
+    #[serde(default)]
+    pub role: String,
+}
+
+// Frames of per-player stats, the last one is the most recent
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Stats {
+    #[serde(default)]
+    frames: Vec<StatsFrame>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StatsFrame {
+    #[serde(default)]
+    participants: Vec<PlayerStats>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerStats {
+    pub participant_id: u8,
+    #[serde(default)]
+    pub kills: u16,
+    #[serde(default)]
+    pub deaths: u16,
+    #[serde(default)]
+    pub assists: u16,
+    #[serde(default)]
+    pub creep_score: u16,
+    #[serde(default)]
+    pub total_gold_earned: u32,
+}
+
+impl GameMetadata {
+    // Ex: "14.19.620.5343" is shown as "14.19"
+    pub fn patch(&self) -> Option<String> {
+        let mut parts = self.patch_version.split('.');
+        match (parts.next(), parts.next()) {
+            (Some(major), Some(minor)) if !major.is_empty() => Some(format!("{}.{}", major, minor)),
+            _ => None,
+        }
+    }
+}
+
+const WINDOW_URL: &str = "https://feed.lolesports.com/livestats/v1/window/";
+
+pub async fn fetch_window(
+    client: &Client,
+    source: &Source,
+    game_id: &str,
+) -> Result<Window, Error> {
+    let url = WINDOW_URL.to_owned() + game_id;
+    let fixture = format!("window-{}.json", game_id);
+    let body = get(client, source, &url, &fixture).await?;
+    parse(&body)
+}
+
+const DETAILS_URL: &str = "https://feed.lolesports.com/livestats/v1/details/";
+
+// Without a starting time the latest frames are returned, which for finished games are the
+// final stats
+pub async fn fetch_player_stats(
+    client: &Client,
+    source: &Source,
+    game_id: &str,
+) -> Result<Vec<PlayerStats>, Error> {
+    let url = DETAILS_URL.to_owned() + game_id;
+    let fixture = format!("details-{}.json", game_id);
+    let body = get(client, source, &url, &fixture).await?;
+    let stats: Stats = parse(&body)?;
+    Ok(stats
+        .frames
+        .into_iter()
+        .next_back()
+        .map(|f| f.participants)
+        .unwrap_or_default())
+}
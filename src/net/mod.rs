@@ -1,7 +1,16 @@
-use reqwest::StatusCode;
-use std::fmt;
+use reqwest::{Certificate, Client, StatusCode};
+use std::{
+    collections::VecDeque,
+    fmt,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tokio::sync::Mutex;
+use tracing::info;
 
+pub mod event_details;
 pub mod leagues;
+pub mod livestats;
 pub mod schedule;
 
 const X_API_KEY_NAME: &str = "x-api-key";
@@ -12,6 +21,9 @@ pub enum Error {
     Http(reqwest::Error),
     Request(StatusCode),
     Deserialize(String),
+    Fixture(std::io::Error),
+    Certificates(std::io::Error),
+    Offline,
 }
 
 impl fmt::Display for Error {
@@ -20,6 +32,9 @@ impl fmt::Display for Error {
             Error::Http(e) => write!(f, "HTTP error: {}", e),
             Error::Request(e) => write!(f, "Request error: {}", e),
             Error::Deserialize(e) => write!(f, "Deserialize error: {}", e),
+            Error::Fixture(e) => write!(f, "Fixture error: {}", e),
+            Error::Certificates(e) => write!(f, "Failed to read CA bundle: {}", e),
+            Error::Offline => write!(f, "Offline mode, not fetching"),
         }
     }
 }
@@ -30,6 +45,9 @@ impl std::error::Error for Error {
             Error::Http(e) => Some(e),
             Error::Request(_) => None,
             Error::Deserialize(_) => None,
+            Error::Fixture(e) => Some(e),
+            Error::Certificates(e) => Some(e),
+            Error::Offline => None,
         }
     }
 }
@@ -39,3 +57,100 @@ impl From<reqwest::Error> for Error {
         Error::Http(error)
     }
 }
+
+// Proxies are taken from the environment, Ex: HTTPS_PROXY. Accepting invalid certificates is
+// for proxies that intercept TLS, a CA bundle adds the PEM certificates of such a proxy
+pub fn client(accept_invalid_certs: bool, ca_bundle: Option<&Path>) -> Result<Client, Error> {
+    let mut builder = Client::builder().danger_accept_invalid_certs(accept_invalid_certs);
+    if let Some(path) = ca_bundle {
+        let pem = std::fs::read(path).map_err(Error::Certificates)?;
+        for certificate in Certificate::from_pem_bundle(&pem)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    Ok(builder.build()?)
+}
+
+// Where API responses come from, fixtures are the raw response bodies, one file per request
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Source {
+    #[default]
+    Network,
+    // Responses are also saved to the directory
+    Record(PathBuf),
+    // Responses are read from the directory instead of the network
+    Replay(PathBuf),
+}
+
+// Caps the requests sent in any minute, shared by everything that fetches. Requests over the
+// limit wait for a free slot in the order they were made
+#[derive(Debug)]
+pub struct RateLimit {
+    per_minute: usize,
+    // When the requests of the last minute were sent, oldest first
+    sent: Mutex<VecDeque<Instant>>,
+}
+
+impl RateLimit {
+    const WINDOW: Duration = Duration::from_secs(60);
+
+    pub fn new(per_minute: usize) -> Self {
+        Self {
+            per_minute: per_minute.max(1),
+            sent: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub async fn acquire(&self) {
+        // The lock is held while waiting, so later requests queue behind this one
+        let mut sent = self.sent.lock().await;
+        let now = Instant::now();
+        while sent
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= Self::WINDOW)
+        {
+            sent.pop_front();
+        }
+        if sent.len() >= self.per_minute
+            && let Some(oldest) = sent.pop_front()
+        {
+            let wait = Self::WINDOW.saturating_sub(now.duration_since(oldest));
+            info!("Request limit reached, waiting {:?}", wait);
+            tokio::time::sleep(wait).await;
+        }
+        sent.push_back(Instant::now());
+    }
+}
+
+// Gets the body of an API response, `fixture` names the file of the response in fixture mode
+async fn get(client: &Client, source: &Source, url: &str, fixture: &str) -> Result<String, Error> {
+    if let Source::Replay(dir) = source {
+        info!("Replaying fixture '{}'", fixture);
+        return tokio::fs::read_to_string(dir.join(fixture))
+            .await
+            .map_err(Error::Fixture);
+    }
+    let response = client
+        .get(url)
+        .header(X_API_KEY_NAME, X_API_KEY_VALUE)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(Error::Request(response.status()));
+    }
+    let body = response.text().await?;
+    if let Source::Record(dir) = source {
+        info!("Recording fixture '{}'", fixture);
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(Error::Fixture)?;
+        tokio::fs::write(dir.join(fixture), &body)
+            .await
+            .map_err(Error::Fixture)?;
+    }
+    Ok(body)
+}
+
+fn parse<T: serde::de::DeserializeOwned>(body: &str) -> Result<T, Error> {
+    serde_json::from_str(body).map_err(|e| Error::Deserialize(e.to_string()))
+}
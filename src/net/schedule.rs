@@ -114,7 +114,7 @@ where
             .map(|s| s == "match")
             .unwrap_or(false)
         {
-            let event: Event = serde_json::from_value(raw).map_err(|e| de::Error::custom(e))?;
+            let event: Event = serde_json::from_value(raw).map_err(de::Error::custom)?;
             filtered.push(event);
         }
     }
@@ -127,28 +127,23 @@ const SCHEDULE_URL: &str =
 
 pub async fn fetch_schedule(
     client: &Client,
+    source: &Source,
     slug: &str,
     page: Option<&str>,
 ) -> Result<Schedule, Error> {
-    let url = match page {
-        Some(token) => SCHEDULE_URL.to_owned() + &slug + "pageToken=" + &token,
-        None => SCHEDULE_URL.to_owned() + &slug,
+    let (url, fixture) = match page {
+        Some(token) => (
+            SCHEDULE_URL.to_owned() + slug + "pageToken=" + token,
+            format!("schedule-{}-{}.json", slug, token),
+        ),
+        None => (
+            SCHEDULE_URL.to_owned() + slug,
+            format!("schedule-{}.json", slug),
+        ),
     };
 
-    let response = client
-        .get(url)
-        .header(X_API_KEY_NAME, X_API_KEY_VALUE)
-        .send()
-        .await?;
-
-    if response.status().is_success() {
-        let api_response: Root = response
-            .json()
-            .await
-            .map_err(|e| Error::Deserialize(e.to_string()))?;
-        info!("{:?}", api_response.data.schedule);
-        return Ok(api_response.data.schedule);
-    } else {
-        return Err(Error::Request(response.status()));
-    }
+    let body = get(client, source, &url, &fixture).await?;
+    let api_response: Root = parse(&body)?;
+    info!("{:?}", api_response.data.schedule);
+    Ok(api_response.data.schedule)
 }
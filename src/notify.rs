@@ -0,0 +1,286 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::{DateTime, Local, TimeDelta};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::{
+    clock::Clock,
+    config::{Config, NotificationRule},
+    hooks,
+    models::{Event, MatchState},
+    resources,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Notice {
+    Reminder,
+    Started,
+    Finished,
+}
+
+impl Notice {
+    fn name(self) -> &'static str {
+        match self {
+            Notice::Reminder => "reminder",
+            Notice::Started => "match_start",
+            Notice::Finished => "match_end",
+        }
+    }
+}
+
+const REMINDERS_FILE: &str = "reminders.json";
+
+// A reminder set on one match, with a copy of the match so it still fires when its league
+// is no longer active
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reminder {
+    pub event: Event,
+    pub before: Duration,
+}
+
+// Desktop notifications and hooks for upcoming, starting and finished matches, checked on every tick
+#[derive(Debug)]
+pub struct Notifier {
+    // Notices that were already sent, by match id
+    notified: HashSet<(String, Notice)>,
+    // Last seen state of every match, to notice when one starts or finishes
+    states: HashMap<String, MatchState>,
+    // Kept in the data dir until they fire or their match starts
+    reminders: Vec<Reminder>,
+    reminders_path: PathBuf,
+    clock: Clock,
+    config: Arc<Config>,
+}
+
+impl Notifier {
+    pub fn new(config: Arc<Config>) -> Self {
+        let reminders_path = config.data_dir.join(REMINDERS_FILE);
+        let reminders = resources::load_json(&reminders_path);
+        Self {
+            notified: HashSet::new(),
+            states: HashMap::new(),
+            reminders,
+            reminders_path,
+            clock: Clock::default(),
+            config,
+        }
+    }
+
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+
+    pub fn reminder(&self, match_id: &str) -> Option<&Reminder> {
+        self.reminders.iter().find(|r| r.event.match_id == match_id)
+    }
+
+    // Replaces the reminder of the match, `None` removes it
+    pub fn set_reminder(&mut self, event: &Event, before: Option<Duration>) -> std::io::Result<()> {
+        self.reminders
+            .retain(|r| r.event.match_id != event.match_id);
+        if let Some(before) = before {
+            self.reminders.push(Reminder {
+                event: event.clone(),
+                before,
+            });
+        }
+        self.save_reminders()
+    }
+
+    fn save_reminders(&self) -> std::io::Result<()> {
+        resources::save_json(&self.reminders_path, &self.reminders)
+    }
+
+    // Reminders fire whether or not notifications are enabled, as they were set on purpose
+    fn check_reminders(&mut self, events: &[&Event], now: DateTime<Local>) {
+        if self.reminders.is_empty() {
+            return;
+        }
+        let count = self.reminders.len();
+        let mut updated = false;
+        let mut due = Vec::new();
+        self.reminders.retain_mut(|reminder| {
+            // Start times move, so the copy follows the match while its league is active
+            if let Some(event) = events
+                .iter()
+                .find(|e| e.match_id == reminder.event.match_id)
+                && reminder.event != **event
+            {
+                reminder.event = (*event).clone();
+                updated = true;
+            }
+            let event = &reminder.event;
+            let before = TimeDelta::from_std(reminder.before).unwrap_or_default();
+            if !matches!(event.state, MatchState::Unstarted(_)) || event.start_time <= now {
+                return false;
+            }
+            if event.start_time - now > before {
+                return true;
+            }
+            due.push(event.clone());
+            false
+        });
+        for event in &due {
+            info!("Reminder for {}", event.match_id);
+            self.notified
+                .insert((event.match_id.to_string(), Notice::Reminder));
+            if let Some(command) = &self.config.hooks.on_reminder {
+                hooks::run(command, hooks::match_env(event, Notice::Reminder.name()));
+            }
+            let minutes = ((event.start_time - now).num_seconds() + 59) / 60;
+            send(
+                &format!("{} match starting soon", event.league_name),
+                &format!("{} starts in {} minutes", self.describe(event), minutes),
+            );
+        }
+        if (updated || self.reminders.len() != count)
+            && let Err(e) = self.save_reminders()
+        {
+            error!("Failed to save reminders: {}", e);
+        }
+    }
+
+    pub fn check(&mut self, events: &[&Event]) {
+        let now = self.clock.now();
+        self.check_reminders(events, now);
+        if !self.config.notifications.enabled && self.config.hooks.is_empty() {
+            return;
+        }
+        for event in events {
+            if event.match_id.is_empty() {
+                continue;
+            }
+            let previous = self
+                .states
+                .insert(event.match_id.to_string(), event.state.clone());
+            let notice = match (previous, &event.state) {
+                (Some(MatchState::Unstarted(_)), MatchState::InProgress(_)) => Notice::Started,
+                (
+                    Some(MatchState::Unstarted(_) | MatchState::InProgress(_)),
+                    MatchState::Completed(_),
+                ) => Notice::Finished,
+                (_, MatchState::Unstarted(_)) if event.start_time > now => Notice::Reminder,
+                _ => continue,
+            };
+            if self
+                .notified
+                .contains(&(event.match_id.to_string(), notice))
+            {
+                continue;
+            }
+            let Some(rule) = self
+                .config
+                .notifications
+                .rules()
+                .iter()
+                .find(|rule| self.wants(rule, event, notice, now))
+            else {
+                continue;
+            };
+            info!(
+                "Notification rule '{}' matched {}",
+                rule.name, event.match_id
+            );
+            self.notified.insert((event.match_id.to_string(), notice));
+
+            let hook = match notice {
+                Notice::Reminder => &self.config.hooks.on_reminder,
+                Notice::Started => &self.config.hooks.on_match_start,
+                Notice::Finished => &self.config.hooks.on_match_end,
+            };
+            if let Some(command) = hook {
+                hooks::run(command, hooks::match_env(event, notice.name()));
+            }
+            if !self.config.notifications.enabled {
+                continue;
+            }
+
+            let (summary, body) = match notice {
+                Notice::Reminder => {
+                    // Rounded up, so a match 4m30s away starts in 5 minutes
+                    let minutes = ((event.start_time - now).num_seconds() + 59) / 60;
+                    (
+                        format!("{} match starting soon", event.league_name),
+                        format!("{} starts in {} minutes", self.describe(event), minutes),
+                    )
+                }
+                Notice::Started => (
+                    format!("{} match started", event.league_name),
+                    format!("{} is live", self.describe(event)),
+                ),
+                // Never includes the score
+                Notice::Finished => (
+                    format!("{} match finished", event.league_name),
+                    format!("{} has finished", self.describe(event)),
+                ),
+            };
+            send(&summary, &body);
+        }
+    }
+
+    fn wants(
+        &self,
+        rule: &NotificationRule,
+        event: &Event,
+        notice: Notice,
+        now: DateTime<Local>,
+    ) -> bool {
+        let wanted = match notice {
+            Notice::Reminder => rule
+                .before
+                .and_then(|before| TimeDelta::from_std(before).ok())
+                .is_some_and(|before| event.start_time - now <= before),
+            // A hook for the notice also enables it
+            Notice::Started => rule.match_started || self.config.hooks.on_match_start.is_some(),
+            Notice::Finished => rule.match_finished || self.config.hooks.on_match_end.is_some(),
+        };
+        let league = rule.leagues.is_empty()
+            || rule.leagues.iter().any(|league| {
+                league.eq_ignore_ascii_case(&event.league_name)
+                    || league.eq_ignore_ascii_case(&event.league_slug)
+            });
+        let teams = rule.teams.is_empty()
+            || event.teams.iter().any(|team| {
+                rule.teams
+                    .iter()
+                    .any(|t| t.eq_ignore_ascii_case(&team.short))
+            });
+        let favorites = !rule.favorites_only
+            || event
+                .teams
+                .iter()
+                .any(|team| self.config.is_favorite(&team.short));
+        wanted && league && teams && favorites && !rule.is_quiet(now.time())
+    }
+
+    // Names the teams only if unplayed matches may be spoiled
+    fn describe(&self, event: &Event) -> String {
+        match (self.config.spoil_matches, event.teams.as_slice()) {
+            (true, [team0, team1, ..]) => format!("{} vs {}", team0.short, team1.short),
+            _ => format!("{} {}", event.league_name, event.block_name),
+        }
+    }
+}
+
+// Sending can block on the notification daemon, so it happens off the event loop
+pub fn send(summary: &str, body: &str) {
+    info!("Notifying: {} - {}", summary, body);
+    let summary = summary.to_string();
+    let body = body.to_string();
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = notify_rust::Notification::new()
+            .appname(env!("CARGO_PKG_NAME"))
+            .summary(&summary)
+            .body(&body)
+            .show()
+        {
+            error!("Failed to send notification: {}", e);
+        }
+    });
+}
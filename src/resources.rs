@@ -1,4 +1,8 @@
-use std::path::PathBuf;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
 
 use chrono::{DateTime, Duration, Local, Utc};
 use reqwest::Client;
@@ -6,34 +10,278 @@ use serde::Serialize;
 use serde::de::DeserializeOwned;
 use tokio::fs;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
+use crate::clock::Clock;
+use crate::models::{self, Event, League, MatchResult, Strategy, Team};
 use crate::net;
-use crate::widgets::events::{Event, MatchResult, Strategy, Team};
-use crate::widgets::leagues::League;
+use crate::net::event_details::EventDetails;
+use crate::net::livestats::{PlayerStats, Window};
 
-#[derive(Debug, Clone)]
+// Failures worth surfacing to the user, besides the log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Report {
+    Error(String),
+    Warning(String),
+}
+
+type Reporter = Arc<dyn Fn(Report) + Send + Sync>;
+
+// Serialized cache files and when they were written, by file name
+type MemoryCache = HashMap<String, (Vec<u8>, DateTime<Local>)>;
+
+// State of the app kept in the data directory, Ex: reminders. A missing file is the default,
+// a broken one is logged and replaced by the default
+pub fn load_json<T: DeserializeOwned + Default>(path: &Path) -> T {
+    match std::fs::read(path) {
+        Ok(content) => serde_json::from_slice(&content).unwrap_or_else(|e| {
+            error!("Failed to parse '{}': {}", path.display(), e);
+            T::default()
+        }),
+        Err(_) => T::default(),
+    }
+}
+
+pub fn save_json<T: Serialize>(path: &Path, data: &T) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_vec(data).map_err(std::io::Error::other)?;
+    std::fs::write(path, content)
+}
+
+// Failed requests kept for the network health popup, oldest are dropped first
+const MAX_FAILURES: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    // Ex: "getSchedule 98767991310872058"
+    pub endpoint: String,
+    // `None` when there was no response
+    pub status: Option<u16>,
+    pub message: String,
+    pub time: DateTime<Local>,
+    // When a later request to the endpoint succeeded
+    pub recovered: Option<DateTime<Local>>,
+}
+
+#[derive(Clone)]
 pub struct ResourceManager {
     cache_dir: PathBuf,
+    // Only use the cache, never fetching
+    offline: bool,
+    // Ignore the cache until the data has been fetched once
+    refresh: bool,
+    // Cache files fetched this session
+    refreshed: Arc<Mutex<HashSet<String>>>,
+    // Never write to `cache_dir`, fetched data goes to `memory` instead
+    read_only: bool,
+    // Shared by the clones
+    memory: Arc<Mutex<MemoryCache>>,
+    source: net::Source,
+    // Shares its connections with the clones
+    client: Client,
+    // Shared by the clones, so every request counts against the same limit
+    rate_limit: Option<Arc<net::RateLimit>>,
+    // Newest last, shared by the clones
+    failures: Arc<Mutex<VecDeque<Failure>>>,
+    reporter: Reporter,
+    // Ages the cache and dates failures, shared with the app
+    clock: Clock,
+}
+
+impl std::fmt::Debug for ResourceManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResourceManager")
+            .field("cache_dir", &self.cache_dir)
+            .field("offline", &self.offline)
+            .field("refresh", &self.refresh)
+            .field("read_only", &self.read_only)
+            .field("source", &self.source)
+            .field("rate_limit", &self.rate_limit)
+            .finish_non_exhaustive()
+    }
 }
 
 impl ResourceManager {
-    pub fn new(data_dir: PathBuf) -> Self {
+    pub fn new(
+        cache_dir: PathBuf,
+        offline: bool,
+        refresh: bool,
+        reporter: impl Fn(Report) + Send + Sync + 'static,
+    ) -> Self {
         Self {
-            cache_dir: data_dir.join("cache"),
+            cache_dir,
+            offline,
+            refresh,
+            refreshed: Arc::new(Mutex::new(HashSet::new())),
+            read_only: false,
+            memory: Arc::new(Mutex::new(HashMap::new())),
+            source: net::Source::Network,
+            client: Client::new(),
+            rate_limit: None,
+            failures: Arc::new(Mutex::new(VecDeque::new())),
+            reporter: Arc::new(reporter),
+            clock: Clock::default(),
         }
     }
 
+    // Replayed fixtures bypass the cache, so every run sees the same data
+    pub fn with_source(mut self, source: net::Source) -> Self {
+        self.source = source;
+        self
+    }
+
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_rate_limit(mut self, per_minute: Option<usize>) -> Self {
+        self.rate_limit = per_minute.map(|limit| Arc::new(net::RateLimit::new(limit)));
+        self
+    }
+
+    // Waits until the request fits in the rate limit, fixtures are not limited
+    async fn throttle(&self) {
+        if let Some(limit) = &self.rate_limit
+            && !matches!(self.source, net::Source::Replay(_))
+        {
+            limit.acquire().await;
+        }
+    }
+
+    pub fn failures(&self) -> Vec<Failure> {
+        match self.failures.lock() {
+            Ok(failures) => failures.iter().cloned().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    // Adds a failed request to the history, a success marks the failures of the endpoint as
+    // recovered
+    fn track<T>(&self, endpoint: &str, result: &Result<T, net::Error>) {
+        let Ok(mut failures) = self.failures.lock() else {
+            return;
+        };
+        let now = self.clock.now();
+        match result {
+            Ok(_) => {
+                for failure in failures
+                    .iter_mut()
+                    .filter(|f| f.endpoint == endpoint && f.recovered.is_none())
+                {
+                    failure.recovered = Some(now);
+                }
+            }
+            Err(e) => {
+                if failures.len() >= MAX_FAILURES {
+                    failures.pop_front();
+                }
+                failures.push_back(Failure {
+                    endpoint: endpoint.to_string(),
+                    status: match e {
+                        net::Error::Request(status) => Some(status.as_u16()),
+                        _ => None,
+                    },
+                    message: e.to_string(),
+                    time: now,
+                    recovered: None,
+                });
+            }
+        }
+    }
+
+    // Reports are sent to the app as toasts
+    #[cfg(feature = "tui")]
+    pub fn from_config(
+        config: &crate::config::Config,
+        sender: tokio::sync::mpsc::UnboundedSender<crate::event::Event>,
+    ) -> Self {
+        use crate::event::{AppEvent, Event};
+        Self::new(
+            config.data_dir.join("cache"),
+            config.offline,
+            config.refresh,
+            move |report| {
+                let _ = sender.send(Event::App(match report {
+                    Report::Error(msg) => AppEvent::Error(msg),
+                    Report::Warning(msg) => AppEvent::Warning(msg),
+                }));
+            },
+        )
+        .with_source(config.source.clone())
+        .with_read_only(config.read_only_cache)
+        .with_rate_limit(config.request_limit)
+        .with_tls(
+            config.danger_accept_invalid_certs,
+            config.ca_bundle.as_deref(),
+        )
+    }
+
+    // Falls back to the default client when the certificates can not be used, reporting why
+    pub fn with_tls(self, accept_invalid_certs: bool, ca_bundle: Option<&Path>) -> Self {
+        if accept_invalid_certs {
+            warn!("TLS certificates of the API are not verified");
+        }
+        match net::client(accept_invalid_certs, ca_bundle) {
+            Ok(client) => self.with_client(client),
+            Err(e) => {
+                error!("Failed to set up the HTTP client: {:?}", e);
+                // The default client verifies certificates, only against the system ones
+                let dropped = match accept_invalid_certs {
+                    true => "ca_bundle and danger_accept_invalid_certs",
+                    false => "ca_bundle",
+                };
+                self.report(Report::Warning(format!(
+                    "Failed to set up TLS, falling back to the default client without {}: {}",
+                    dropped, e
+                )));
+                self
+            }
+        }
+    }
+
+    fn needs_refresh(&self, name: &str) -> bool {
+        matches!(self.source, net::Source::Replay(_))
+            || self.refresh && !self.refreshed.lock().unwrap().contains(name)
+    }
+
+    // Surfaces a failure to the user, besides logging it
+    fn report(&self, report: Report) {
+        (self.reporter)(report);
+    }
+
     async fn cache_data<T: Serialize>(&self, name: &str, data: &T) -> std::io::Result<()> {
+        // Only fetched data is cached, so it is fresh even if writing it fails
+        self.refreshed.lock().unwrap().insert(name.to_string());
+        if matches!(self.source, net::Source::Replay(_)) {
+            return Ok(());
+        }
+        let serialized = serde_json::to_vec(data).map_err(std::io::Error::other)?;
+        if self.read_only {
+            self.memory
+                .lock()
+                .unwrap()
+                .insert(name.to_string(), (serialized, self.clock.now()));
+            return Ok(());
+        }
         let cache_path = self.cache_dir.join(name);
 
         if let Some(parent) = cache_path.parent() {
             fs::create_dir_all(parent).await?;
         }
 
-        let serialized = serde_json::to_vec(data)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-
         let mut file = fs::File::create(&cache_path).await?;
         file.write_all(&serialized).await?;
 
@@ -44,6 +292,12 @@ impl ResourceManager {
         &self,
         name: &str,
     ) -> std::io::Result<(T, DateTime<Local>)> {
+        // Data fetched this session is newer than the files in the cache directory
+        let cached = self.memory.lock().unwrap().get(name).cloned();
+        if let Some((contents, time)) = cached {
+            let data = serde_json::from_slice(&contents).map_err(std::io::Error::other)?;
+            return Ok((data, time));
+        }
         let cache_path = self.cache_dir.join(name);
 
         let mut file = fs::File::open(&cache_path).await?;
@@ -54,30 +308,59 @@ impl ResourceManager {
         let modified_time = metadata.modified()?;
         let modified_datetime: DateTime<Local> = modified_time.into();
 
-        let data = serde_json::from_slice(&contents)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        let data = serde_json::from_slice(&contents).map_err(std::io::Error::other)?;
 
         Ok((data, modified_datetime))
     }
 
+    // Loads cached data regardless of its age
+    async fn load_offline<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        match self.load_from_cache(name).await {
+            Ok((data, _)) => Some(data),
+            Err(e) => {
+                info!("Failed to load cached '{}': {:?}", name, e);
+                self.report(Report::Warning(format!(
+                    "Offline mode, nothing cached for '{}'",
+                    name.trim_end_matches(".json")
+                )));
+                None
+            }
+        }
+    }
+
     pub async fn get_leagues(&self) -> Option<Vec<League>> {
+        if self.offline {
+            return self.load_offline("leagues.json").await;
+        }
         match self.load_from_cache("leagues.json").await {
+            Ok(_) if self.needs_refresh("leagues.json") => info!("Ignoring cached leagues"),
             Ok((leagues, cached_time)) => 'fetch: {
                 info!("Successfully loaded cached leagues");
-                let now = Local::now();
+                let now = self.clock.now();
 
                 if cached_time < now - Duration::days(7) {
                     info!("Cached leagues is older then 7 days, fetching new list");
                     break 'fetch;
                 }
+
+                let leagues: Vec<League> = leagues;
+                if leagues.iter().any(|l| l.slug.is_empty()) {
+                    info!("Cached leagues are missing slugs, fetching new list");
+                    break 'fetch;
+                }
+                if leagues.len() > 1 && leagues.iter().all(|l| l.priority == 0) {
+                    info!("Cached leagues are missing priorities, fetching new list");
+                    break 'fetch;
+                }
                 return Some(leagues);
             }
             Err(e) => info!("Failed to load cached leagues: {:?}", e),
         }
 
-        let client = Client::new();
-
-        match net::leagues::fetch_leagues(&client).await {
+        self.throttle().await;
+        let result = net::leagues::fetch_leagues(&self.client, &self.source).await;
+        self.track("getLeagues", &result);
+        match result {
             Ok(leagues) => {
                 info!(
                     "Successfully fetched leagues from API, total leagues: {}",
@@ -86,13 +369,35 @@ impl ResourceManager {
                 let leagues = leagues.into_iter().map(League::from).collect();
                 match self.cache_data("leagues.json", &leagues).await {
                     Ok(_) => info!("Successfully cached leagues"),
-                    Err(e) => error!("Failed to cache leagues: {:?}", e),
+                    Err(e) => {
+                        error!("Failed to cache leagues: {:?}", e);
+                        self.report(Report::Warning(format!("Failed to cache leagues: {}", e)));
+                    }
                 }
                 return Some(leagues);
             }
-            Err(e) => error!("Failed to fetch leagues: {:?}", e),
+            Err(e) => {
+                error!("Failed to fetch leagues: {:?}", e);
+                self.report(Report::Error(format!("Failed to fetch leagues: {}", e)));
+            }
         }
-        return None;
+        None
+    }
+
+    // Leagues of `ids` whose cached schedule has a match yet to start, nothing is fetched
+    pub async fn leagues_with_upcoming(&self, ids: &[String]) -> HashSet<String> {
+        let now = self.clock.now();
+        let mut upcoming = HashSet::new();
+        for id in ids {
+            if let Ok((events, _)) = self
+                .load_from_cache::<Vec<Event>>(&format!("{}.json", id))
+                .await
+                && events.iter().any(|e| e.is_upcoming(now))
+            {
+                upcoming.insert(id.to_string());
+            }
+        }
+        upcoming
     }
 
     pub async fn get_schedule(&self, slug: &str) -> Option<Vec<Event>> {
@@ -102,10 +407,16 @@ impl ResourceManager {
 
         let cache_path = format!("{}.json", slug);
 
+        if self.offline {
+            return self.load_offline(&cache_path).await;
+        }
         match self.load_from_cache(&cache_path).await {
+            Ok(_) if self.needs_refresh(&cache_path) => {
+                info!("Ignoring cached schedule '{}'", slug)
+            }
             Ok((events, cached_time)) => 'fetch: {
                 info!("Successfully loaded cached schedule '{}'", slug);
-                let now = Local::now();
+                let now = self.clock.now();
 
                 if cached_time < now - Duration::days(3) {
                     info!("Cached schedule is older then 3 days, need to fetch newer");
@@ -118,6 +429,15 @@ impl ResourceManager {
                 }
 
                 let events: Vec<Event> = events;
+                // Caches written before ids were kept, dedup and selection need them
+                if events
+                    .iter()
+                    .any(|e| e.match_id.is_empty() || e.league_slug.is_empty())
+                {
+                    info!("Cached schedule is missing match ids or slugs, fetching new");
+                    break 'fetch;
+                }
+
                 let has_invalid_event = events
                     .iter()
                     .any(|e| e.state.get_string() == "Unstarted" && e.start_time < now);
@@ -131,9 +451,41 @@ impl ResourceManager {
             Err(e) => info!("Failed to load cached schedule '{}': {:?}", slug, e),
         }
 
-        let client = Client::new();
+        self.fetch_schedule(slug).await
+    }
 
-        match net::schedule::fetch_schedule(&client, slug, None).await {
+    // The cached schedule regardless of its age, shown while a newer one is fetched.
+    // Caches without match ids are left out, dedup and selection need them
+    pub async fn get_cached_schedule(&self, slug: &str) -> Option<Vec<Event>> {
+        match self
+            .load_from_cache::<Vec<Event>>(&format!("{}.json", slug))
+            .await
+        {
+            Ok((events, _))
+                if !events
+                    .iter()
+                    .any(|e| e.match_id.is_empty() || e.league_slug.is_empty()) =>
+            {
+                Some(events)
+            }
+            Ok(_) => None,
+            Err(e) => {
+                info!("Failed to load cached schedule '{}': {:?}", slug, e);
+                None
+            }
+        }
+    }
+
+    // Fetches the schedule from the API regardless of the cache state
+    pub async fn fetch_schedule(&self, slug: &str) -> Option<Vec<Event>> {
+        let cache_path = format!("{}.json", slug);
+        if self.offline {
+            return self.load_offline(&cache_path).await;
+        }
+        self.throttle().await;
+        let result = net::schedule::fetch_schedule(&self.client, &self.source, slug, None).await;
+        self.track(&format!("getSchedule {}", slug), &result);
+        match result {
             Ok(schedule) => {
                 info!(
                     "Successfully fetched schedule from API, slug: {}, pages: (before: {:?} after: {:?}) total events: {}",
@@ -142,16 +494,99 @@ impl ResourceManager {
                     schedule.pages.newer,
                     schedule.events.len()
                 );
-                let events = schedule.events.into_iter().map(Event::from).collect();
+                let mut skipped = 0;
+                let events: Vec<Event> = schedule
+                    .events
+                    .into_iter()
+                    .filter_map(|net_event| {
+                        let start_time = net_event.start_time.clone();
+                        Event::try_from(net_event)
+                            .inspect_err(|e| {
+                                error!("Skipping match starting at '{}': {:?}", start_time, e);
+                                skipped += 1;
+                            })
+                            .ok()
+                    })
+                    .collect();
+                if skipped > 0 {
+                    self.report(Report::Warning(format!(
+                        "Skipped {} matches with invalid start times in schedule '{}'",
+                        skipped, slug
+                    )));
+                }
                 match self.cache_data(&cache_path, &events).await {
                     Ok(_) => info!("Successfully cached schedule '{}'", slug),
-                    Err(e) => error!("Failed to cache schedule '{}': {:?}", slug, e),
+                    Err(e) => {
+                        error!("Failed to cache schedule '{}': {:?}", slug, e);
+                        self.report(Report::Warning(format!(
+                            "Failed to cache schedule '{}': {}",
+                            slug, e
+                        )));
+                    }
                 }
                 return Some(events);
             }
-            Err(e) => error!("Failed to fetch schedule: {:?}", e),
+            Err(e) => {
+                error!("Failed to fetch schedule: {:?}", e);
+                self.report(Report::Error(format!(
+                    "Failed to fetch schedule '{}': {}",
+                    slug, e
+                )));
+            }
+        }
+        None
+    }
+
+    // Fetches the streams and VODs of a match, these change too often to be cached
+    pub async fn fetch_event_details(&self, match_id: &str) -> Result<EventDetails, net::Error> {
+        if self.offline {
+            return Err(net::Error::Offline);
+        }
+        self.throttle().await;
+        let result =
+            net::event_details::fetch_event_details(&self.client, &self.source, match_id).await;
+        self.track(&format!("getEventDetails {}", match_id), &result);
+        result
+    }
+
+    // Fetches the patch and champions of a game, not cached as live games still change
+    pub async fn fetch_game_window(&self, game_id: &str) -> Result<Window, net::Error> {
+        if self.offline {
+            return Err(net::Error::Offline);
+        }
+        self.throttle().await;
+        let result = net::livestats::fetch_window(&self.client, &self.source, game_id).await;
+        self.track(&format!("window {}", game_id), &result);
+        result
+    }
+
+    // The stats of finished games no longer change, so they are cached for good
+    pub async fn get_player_stats(
+        &self,
+        game_id: &str,
+        finished: bool,
+    ) -> Result<Vec<PlayerStats>, net::Error> {
+        let name = format!("stats-{}.json", game_id);
+        if finished && !self.needs_refresh(&name) {
+            match self.load_from_cache(&name).await {
+                Ok((stats, _)) => return Ok(stats),
+                Err(e) => info!("Failed to load cached '{}': {:?}", name, e),
+            }
         }
-        return None;
+        if self.offline {
+            return Err(net::Error::Offline);
+        }
+        self.throttle().await;
+        let result = net::livestats::fetch_player_stats(&self.client, &self.source, game_id).await;
+        self.track(&format!("details {}", game_id), &result);
+        let stats = result?;
+        if finished
+            && !stats.is_empty()
+            && let Err(e) = self.cache_data(&name, &stats).await
+        {
+            error!("Failed to cache '{}': {:?}", name, e);
+        }
+        Ok(stats)
     }
 }
 
@@ -160,21 +595,27 @@ impl From<net::leagues::League> for League {
         Self {
             id: net_league.id,
             name: net_league.name,
+            slug: net_league.slug,
             region: net_league.region,
+            priority: net_league.priority,
             selected: false,
         }
     }
 }
 
-impl From<net::schedule::Event> for Event {
-    fn from(net_event: net::schedule::Event) -> Self {
-        Self {
+// Events without a valid start time can not be placed in the schedule
+impl TryFrom<net::schedule::Event> for Event {
+    type Error = chrono::ParseError;
+
+    fn try_from(net_event: net::schedule::Event) -> Result<Self, Self::Error> {
+        Ok(Self {
             start_time: net_event
                 .start_time
-                .parse::<DateTime<Utc>>()
-                .unwrap()
+                .parse::<DateTime<Utc>>()?
                 .with_timezone(&Local),
+            match_id: net_event.match_field.id.clone(),
             league_name: net_event.league.name,
+            league_slug: net_event.league.slug,
             block_name: net_event.block_name,
             strategy: Strategy {
                 strat_type: net_event.match_field.strategy.type_field.clone().into(),
@@ -182,22 +623,25 @@ impl From<net::schedule::Event> for Event {
             },
             state: net_event.state.into(),
             result: (&net_event.match_field).into(),
-            teams: net_event
-                .match_field
-                .teams
-                .into_iter()
-                .map(|team| Team {
-                    name: team.name,
-                    short: team.code,
-                })
-                .collect(),
-        }
+            teams: models::two_teams(
+                net_event
+                    .match_field
+                    .teams
+                    .into_iter()
+                    .map(|team| Team {
+                        name: team.name,
+                        short: team.code,
+                    })
+                    .collect(),
+            ),
+        })
     }
 }
 
 impl From<&net::schedule::Match> for Option<MatchResult> {
     fn from(net_match: &net::schedule::Match) -> Option<MatchResult> {
-        if let (Some(rec0), Some(rec1)) = (&net_match.teams[0].result, &net_match.teams[1].result) {
+        let result = |i: usize| net_match.teams.get(i).and_then(|t| t.result.as_ref());
+        if let (Some(rec0), Some(rec1)) = (result(0), result(1)) {
             Some(MatchResult {
                 game_wins: (rec0.game_wins as u16, rec1.game_wins as u16),
             })
@@ -0,0 +1,224 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+    time,
+};
+use tracing::{info, warn};
+
+use crate::{
+    clock,
+    config::{Config, TeamOverride},
+    event::{AppEvent, Event},
+    export, headless,
+    models::{Event as Match, League},
+    resources::ResourceManager,
+};
+
+// Requests are only a request line and a few headers, anything longer is refused
+const MAX_REQUEST: usize = 8192;
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Serves `.ics` feeds over HTTP, with one feed per league and `all.ics` combining the default
+// leagues, so calendar apps can subscribe to them
+pub async fn serve(
+    config: Config,
+    bind: IpAddr,
+    port: u16,
+    interval: Option<Duration>,
+    spoilers: bool,
+) -> color_eyre::Result<()> {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let resources = ResourceManager::from_config(&config, sender);
+    let leagues = resources.get_leagues().await.unwrap_or_default();
+    headless::report_messages(&mut receiver);
+    if leagues.is_empty() {
+        color_eyre::eyre::bail!("No leagues available to serve");
+    }
+    let mut selected = Vec::new();
+    for name in &config.default_leagues {
+        match leagues.iter().find(|l| l.matches_name(name)) {
+            Some(league) => selected.push(league.id.to_string()),
+            None => eprintln!("Could not find league '{}'", name),
+        }
+    }
+
+    let listener = TcpListener::bind((bind, port)).await?;
+    eprintln!("Serving calendars on http://{}/", listener.local_addr()?);
+    tokio::spawn(async move {
+        while let Some(event) = receiver.recv().await {
+            if let Event::App(AppEvent::Error(msg) | AppEvent::Warning(msg)) = event {
+                eprintln!("{}", msg);
+            }
+        }
+    });
+
+    let interval = interval
+        .or(config.refresh_interval)
+        .unwrap_or(Duration::from_secs(30 * 60));
+    let feeds = Arc::new(Feeds {
+        resources,
+        leagues,
+        selected,
+        spoilers,
+        interval,
+        config,
+        schedules: Mutex::new(HashMap::new()),
+    });
+    loop {
+        let (stream, address) = listener.accept().await?;
+        let feeds = feeds.clone();
+        tokio::spawn(async move {
+            if let Err(e) = feeds.handle(stream).await {
+                warn!("Failed to handle request from {}: {:?}", address, e);
+            }
+        });
+    }
+}
+
+struct Feeds {
+    resources: ResourceManager,
+    leagues: Vec<League>,
+    // Ids of the leagues in `all.ics`
+    selected: Vec<String>,
+    config: Config,
+    // Whether the feeds spoil everything, whatever the config says
+    spoilers: bool,
+    // How long a schedule is served before it is fetched again
+    interval: Duration,
+    schedules: Mutex<HashMap<String, (Instant, Vec<Match>)>>,
+}
+
+impl Feeds {
+    async fn handle(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        let mut request = Vec::new();
+        let mut buffer = [0; 1024];
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+            let read = match time::timeout(READ_TIMEOUT, stream.read(&mut buffer)).await {
+                Ok(read) => read?,
+                Err(_) => return respond(&mut stream, "408 Request Timeout", "").await,
+            };
+            if read == 0 || request.len() + read > MAX_REQUEST {
+                return respond(&mut stream, "400 Bad Request", "Bad request\n").await;
+            }
+            request.extend_from_slice(&buffer[..read]);
+        }
+        let request = String::from_utf8_lossy(&request);
+        let mut parts = request
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .split_whitespace();
+        let (method, target) = (
+            parts.next().unwrap_or_default(),
+            parts.next().unwrap_or("/"),
+        );
+        info!("{} {}", method, target);
+        if method != "GET" && method != "HEAD" {
+            return respond(&mut stream, "405 Method Not Allowed", "").await;
+        }
+
+        let path = target
+            .split('?')
+            .next()
+            .unwrap_or_default()
+            .trim_start_matches('/');
+        let (status, content_type, body) = match path {
+            "" => ("200 OK", "text/plain; charset=utf-8", self.index()),
+            "all.ics" => {
+                let ids = self.selected.clone();
+                let body = self.feed(&ids, env!("CARGO_PKG_NAME")).await;
+                ("200 OK", "text/calendar; charset=utf-8", body)
+            }
+            path => match path
+                .strip_suffix(".ics")
+                .and_then(|name| self.leagues.iter().find(|l| l.matches_name(name)))
+            {
+                Some(league) => {
+                    let name = format!("{} {}", env!("CARGO_PKG_NAME"), league.name);
+                    let body = self.feed(&[league.id.to_string()], &name).await;
+                    ("200 OK", "text/calendar; charset=utf-8", body)
+                }
+                None => ("404 Not Found", "text/plain", "Not found\n".to_string()),
+            },
+        };
+        // HEAD responses describe the body without sending it
+        let head = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            status,
+            content_type,
+            body.len()
+        );
+        stream.write_all(head.as_bytes()).await?;
+        if method != "HEAD" {
+            stream.write_all(body.as_bytes()).await?;
+        }
+        stream.shutdown().await
+    }
+
+    fn index(&self) -> String {
+        let mut index = String::from("all.ics - the default leagues\n");
+        for league in &self.leagues {
+            let slug = if league.slug.is_empty() {
+                &league.id
+            } else {
+                &league.slug
+            };
+            index.push_str(&format!("{}.ics - {}\n", slug, league.name));
+        }
+        index
+    }
+
+    async fn feed(&self, ids: &[String], name: &str) -> String {
+        let mut events = Vec::new();
+        for id in ids {
+            events.extend(self.schedule(id).await);
+        }
+        events.sort_by_key(|e| e.start_time);
+        TeamOverride::apply(&self.config.team_overrides, &mut events);
+        let spoilers = headless::spoilers(&self.config, self.spoilers);
+        export::to_ics_feed(
+            &events,
+            spoilers,
+            &self.config,
+            clock::now(),
+            name,
+            self.interval,
+        )
+    }
+
+    // The first request may be served from the cache, after that schedules are kept for
+    // `interval` and then fetched again, falling back to the last schedule when fetching fails
+    async fn schedule(&self, id: &str) -> Vec<Match> {
+        let previous = self.schedules.lock().unwrap().get(id).cloned();
+        let events = match previous {
+            Some((fetched, events)) if fetched.elapsed() < self.interval => return events,
+            Some((_, events)) => self.resources.fetch_schedule(id).await.unwrap_or(events),
+            None => self.resources.get_schedule(id).await.unwrap_or_default(),
+        };
+        self.schedules
+            .lock()
+            .unwrap()
+            .insert(id.to_string(), (Instant::now(), events.clone()));
+        events
+    }
+}
+
+// Plain text responses for malformed requests
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) -> std::io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await
+}
@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Modifier,
+    text::{Line, Span},
+    widgets::{Clear, Widget, WidgetRef},
+};
+
+use crate::config::Config;
+
+#[derive(Debug)]
+pub struct CommandLine {
+    pub input: String,
+    config: Arc<Config>,
+}
+
+impl CommandLine {
+    pub fn new(config: Arc<Config>) -> Self {
+        Self {
+            input: String::new(),
+            config,
+        }
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.input.push(c);
+    }
+
+    pub fn pop(&mut self) -> Option<char> {
+        self.input.pop()
+    }
+}
+
+impl WidgetRef for &CommandLine {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let styles = &self.config.style;
+        let cursor = Span::from(" ").style(styles.default.add_modifier(Modifier::REVERSED));
+
+        Clear.render(area, buf);
+        Line::from(vec![
+            Span::from(":").style(styles.highlight),
+            Span::from(self.input.as_str()).style(styles.default),
+            cursor,
+        ])
+        .render(area, buf);
+    }
+}
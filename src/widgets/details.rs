@@ -0,0 +1,368 @@
+use std::{cell::Cell, collections::HashMap, sync::Arc, time::Duration};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Color,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, WidgetRef},
+};
+
+use crate::{
+    config::{Config, strings::Strings},
+    models::{Event, MatchState},
+    net::livestats::{Participant, PlayerStats, Window},
+};
+
+const DETAILS_WIDTH: u16 = 64;
+const DETAILS_HEIGHT: u16 = 24;
+
+// A game of a match with what the livestats API knows about it
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameDetails {
+    pub id: String,
+    pub number: i64,
+    pub state: String,
+    // Codes of the blue and the red team
+    pub teams: [String; 2],
+    // `None` when livestats has no data for the game
+    pub window: Option<Window>,
+}
+
+#[derive(Debug)]
+pub struct Details {
+    event: Event,
+    // `None` while loading, an error message when fetching failed
+    games: Option<Result<Vec<GameDetails>, String>>,
+    // Index into `games` of the game whose player stats are shown
+    selected: usize,
+    // Player stats by game id, `None` while they are fetched
+    stats: HashMap<String, Option<Result<Vec<PlayerStats>, String>>>,
+    // Series wins of each team against the other and the matches they played
+    head_to_head: (u16, u16, usize),
+    scroll: u16,
+    // Set when rendering, so scrolling stops once the last line is at the bottom
+    max_scroll: Cell<u16>,
+    pub spoil_results: bool,
+    config: Arc<Config>,
+}
+
+impl Details {
+    pub fn new(event: Event, spoil_results: bool, config: Arc<Config>) -> Self {
+        Self {
+            event,
+            games: None,
+            selected: 0,
+            stats: HashMap::new(),
+            head_to_head: (0, 0, 0),
+            scroll: 0,
+            max_scroll: Cell::new(0),
+            spoil_results,
+            config,
+        }
+    }
+
+    pub fn match_id(&self) -> &str {
+        &self.event.match_id
+    }
+
+    pub fn set_head_to_head(&mut self, record: (u16, u16, usize)) {
+        self.head_to_head = record;
+    }
+
+    pub fn set_games(&mut self, games: Result<Vec<GameDetails>, String>) {
+        self.games = Some(games);
+    }
+
+    pub fn set_stats(&mut self, game_id: &str, stats: Result<Vec<PlayerStats>, String>) {
+        if let Some(entry) = self.stats.get_mut(game_id) {
+            *entry = Some(stats);
+        }
+    }
+
+    // The id of the selected game and whether it is finished, if its stats are still needed.
+    // The game is then marked as fetching, so it is only requested once
+    pub fn request_stats(&mut self) -> Option<(String, bool)> {
+        if !self.spoil_results {
+            return None;
+        }
+        let game = self.games.as_ref()?.as_ref().ok()?.get(self.selected)?;
+        if game.window.is_none() || self.stats.contains_key(&game.id) {
+            return None;
+        }
+        self.stats.insert(game.id.clone(), None);
+        Some((game.id.clone(), game.state == "completed"))
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn select_next(&mut self) {
+        let count = self
+            .games
+            .as_ref()
+            .and_then(|g| g.as_ref().ok())
+            .map_or(0, Vec::len);
+        if self.selected + 1 < count {
+            self.selected += 1;
+        }
+    }
+
+    pub fn up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub fn down(&mut self) {
+        self.scroll = (self.scroll + 1).min(self.max_scroll.get());
+    }
+
+    fn lines(&self) -> Vec<Line<'_>> {
+        let styles = &self.config.style;
+        let strings = &self.config.strings;
+        let mut lines = vec![
+            Line::from(format!(
+                "{} {} {}",
+                self.event.teams[0].name, strings.vs, self.event.teams[1].name
+            ))
+            .style(styles.title()),
+            Line::from(format!(
+                "{} - {}, {} {}",
+                self.event.league_name,
+                self.event.block_name,
+                strings.strategy(&self.event.strategy.strat_type),
+                self.event.strategy.count
+            ))
+            .style(styles.default),
+        ];
+        if let Some(end) = self.estimated_end() {
+            lines.push(Line::from(end).style(styles.default));
+        }
+        lines.push(Line::default());
+        lines.push(Line::from(self.head_to_head()).style(styles.default));
+        lines.push(Line::default());
+        if matches!(self.event.state, MatchState::Unstarted(_)) {
+            lines.push(Line::from(strings.not_started.as_str()).style(styles.default));
+            return lines;
+        }
+
+        let games = match &self.games {
+            None => {
+                lines.push(Line::from(strings.loading.as_str()).style(styles.default));
+                return lines;
+            }
+            Some(Err(message)) => {
+                lines.push(Line::from(message.as_str()).style(styles.default));
+                return lines;
+            }
+            Some(Ok(games)) => games,
+        };
+        // How many games were played gives away the result of a series
+        if !self.spoil_results {
+            lines.push(Line::from(strings.picks_hidden.as_str()).style(styles.default));
+            return lines;
+        }
+        if games.is_empty() {
+            lines.push(Line::from(strings.no_games.as_str()).style(styles.default));
+        }
+        for (i, game) in games.iter().enumerate() {
+            let mut header = strings.game_title.replace("{}", &game.number.to_string());
+            if let Some(patch) = game.window.as_ref().and_then(|w| w.game_metadata.patch()) {
+                header.push_str(&format!(", {}", strings.patch.replace("{}", &patch)));
+            }
+            if game.state == "inProgress" {
+                header.push_str(&format!(", {}", strings.in_progress));
+            }
+            if i == self.selected {
+                lines.push(Line::from(format!("* {}", header)).style(styles.selected));
+            } else {
+                lines.push(Line::from(format!("  {}", header)).style(styles.date()));
+            }
+
+            let Some(window) = &game.window else {
+                lines.push(Line::from(format!("  {}", strings.no_picks)).style(styles.default));
+                lines.push(Line::default());
+                continue;
+            };
+            let metadata = &window.game_metadata;
+            if i == self.selected
+                && let Some(stats) = self.stats.get(&game.id)
+            {
+                match stats {
+                    Some(Ok(stats)) if !stats.is_empty() => {
+                        self.push_stats(&mut lines, game, window, stats);
+                        continue;
+                    }
+                    Some(Ok(_)) => lines
+                        .push(Line::from(format!("  {}", strings.no_stats)).style(styles.default)),
+                    Some(Err(message)) => {
+                        lines.push(Line::from(format!("  {}", message)).style(styles.default))
+                    }
+                    None => lines.push(
+                        Line::from(format!("  {}", strings.loading_stats)).style(styles.default),
+                    ),
+                }
+            }
+            let blue = &metadata.blue_team_metadata.participant_metadata;
+            let red = &metadata.red_team_metadata.participant_metadata;
+            lines.push(Line::from(vec![
+                Span::from(format!("  {:<8}", "")),
+                Span::from(format!("{:<16}", game.teams[0])).style(styles.highlight),
+                Span::from(game.teams[1].as_str()).style(styles.highlight),
+            ]));
+            for i in 0..blue.len().max(red.len()) {
+                let role = blue.get(i).or(red.get(i)).map_or("", |p| p.role.as_str());
+                let champion = |team: &[Participant]| {
+                    team.get(i).map_or(String::new(), |p| p.champion_id.clone())
+                };
+                lines.push(
+                    Line::from(format!(
+                        "  {:<8}{:<16}{}",
+                        role,
+                        champion(blue),
+                        champion(red)
+                    ))
+                    .style(styles.default),
+                );
+            }
+            lines.push(Line::default());
+        }
+        lines
+    }
+
+    // Only for series yet to finish, Ex: "Ends around 20:30, after about 2h 30m"
+    fn estimated_end(&self) -> Option<String> {
+        if !matches!(
+            self.event.state,
+            MatchState::Unstarted(_) | MatchState::InProgress(_)
+        ) {
+            return None;
+        }
+        let duration = self.config.series_duration(&self.event.strategy)?;
+        let start = self.event.start_time;
+        let end = start.checked_add_signed(chrono::Duration::from_std(duration).ok()?)?;
+        let format = match end.date_naive() == start.date_naive() {
+            true => "%H:%M",
+            false => "%a %H:%M",
+        };
+        Some(
+            self.config
+                .strings
+                .ends_around
+                .replace("{time}", &end.format(format).to_string())
+                .replace("{length}", &format_length(duration)),
+        )
+    }
+
+    // Ex: "Head-to-head: T1 2 - 1 GEN in 3 matches", of the fetched schedules
+    fn head_to_head(&self) -> String {
+        // Past series would give away who is the stronger team, or the result of this one
+        let strings = &self.config.strings;
+        if !self.spoil_results {
+            return strings.head_to_head_hidden.clone();
+        }
+        let (team0, team1) = (&self.event.teams[0].short, &self.event.teams[1].short);
+        match self.head_to_head {
+            (_, _, 0) => strings
+                .never_played
+                .replace("{team1}", team0)
+                .replace("{team2}", team1),
+            (wins0, wins1, played) => {
+                let record = format!("{} {} - {} {}", team0, wins0, wins1, team1);
+                Strings::count(played, &strings.head_to_head_one, &strings.head_to_head)
+                    .replace("{record}", &record)
+            }
+        }
+    }
+
+    // One row per player, Ex: "  mid     T1 Faker      Ahri        3/1/5     245  12.3k"
+    fn push_stats(
+        &self,
+        lines: &mut Vec<Line<'_>>,
+        game: &GameDetails,
+        window: &Window,
+        stats: &[PlayerStats],
+    ) {
+        let styles = &self.config.style;
+        let metadata = &window.game_metadata;
+        let teams = [&metadata.blue_team_metadata, &metadata.red_team_metadata];
+        for (code, team) in game.teams.iter().zip(teams) {
+            lines.push(
+                Line::from(format!(
+                    "  {:<8}{:<14}{:<12}{:<9}{:>4}{:>7}",
+                    code, "Player", "Champion", "K/D/A", "CS", "Gold"
+                ))
+                .style(styles.highlight),
+            );
+            for player in &team.participant_metadata {
+                let Some(stat) = stats
+                    .iter()
+                    .find(|s| s.participant_id == player.participant_id)
+                else {
+                    continue;
+                };
+                lines.push(
+                    Line::from(format!(
+                        "  {:<8}{:<14}{:<12}{:<9}{:>4}{:>7}",
+                        player.role,
+                        truncate(&player.summoner_name, 13),
+                        truncate(&player.champion_id, 11),
+                        format!("{}/{}/{}", stat.kills, stat.deaths, stat.assists),
+                        stat.creep_score,
+                        format!("{:.1}k", stat.total_gold_earned as f64 / 1000.0)
+                    ))
+                    .style(styles.default),
+                );
+            }
+        }
+        lines.push(Line::default());
+    }
+}
+
+// Ex: "15m", "1h" or "2h 30m", rounded up to whole minutes. Also used for reminder lead times
+pub fn format_length(duration: Duration) -> String {
+    let minutes = duration.as_secs().div_ceil(60);
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h {}m", hours, minutes),
+    }
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
+
+impl WidgetRef for &Details {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let styles = &self.config.style;
+        let width = DETAILS_WIDTH.min(area.width);
+        let height = DETAILS_HEIGHT.min(area.height);
+        let popup_area = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        let block = Block::new()
+            .borders(Borders::all())
+            .border_type(styles.popup_border())
+            .border_style(styles.highlight.bg(Color::Reset))
+            .title(
+                Line::from(self.config.strings.details.as_str())
+                    .centered()
+                    .style(styles.title()),
+            );
+        let inner_area = block.inner(popup_area);
+        Clear.render(popup_area, buf);
+        block.render(popup_area, buf);
+
+        let lines = self.lines();
+        let max_scroll = (lines.len() as u16).saturating_sub(inner_area.height);
+        self.max_scroll.set(max_scroll);
+        Paragraph::new(lines)
+            .scroll((self.scroll.min(max_scroll), 0))
+            .render(inner_area, buf);
+    }
+}
@@ -1,102 +1,28 @@
-use chrono::{DateTime, Local, NaiveDate};
+use chrono::{DateTime, Datelike, Local, NaiveDate};
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Layout, Rect},
-    style::{Color, Modifier, Stylize},
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Color, Modifier, Style, Stylize},
     symbols::line,
-    text::{Line, Text},
+    text::{Line, Span, Text},
     widgets::{Block, Borders, Clear, StatefulWidgetRef, Widget, WidgetRef},
 };
-use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, rc::Rc};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt::Write,
+    ops::RangeBounds,
+    path::PathBuf,
+    sync::Arc,
+};
 use tracing::{debug, info};
 
-use crate::config::Config;
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum StratType {
-    BestOf(String),
-    PlayAll(String),
-    Unknown(String),
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct Strategy {
-    pub strat_type: StratType,
-    pub count: u16,
-}
-
-impl StratType {
-    pub fn get_string(&self) -> &str {
-        match self {
-            StratType::BestOf(s) => s,
-            StratType::PlayAll(s) => s,
-            StratType::Unknown(s) => s,
-        }
-    }
-}
-
-impl From<String> for StratType {
-    fn from(name: String) -> Self {
-        match name.as_str() {
-            "bestOf" => StratType::BestOf("Best of".to_string()),
-            "playAll" => StratType::PlayAll("Play all".to_string()),
-            _ => StratType::Unknown(name),
-        }
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum MatchState {
-    Completed(String),
-    InProgress(String),
-    Unstarted(String),
-    Unknown(String),
-}
-
-impl MatchState {
-    pub fn get_string(&self) -> &str {
-        match self {
-            MatchState::Completed(s) => s,
-            MatchState::InProgress(s) => s,
-            MatchState::Unstarted(s) => s,
-            MatchState::Unknown(s) => s,
-        }
-    }
-}
-
-impl From<String> for MatchState {
-    fn from(name: String) -> Self {
-        match name.as_str() {
-            "completed" => MatchState::Completed("Completed".to_string()),
-            "inProgress" => MatchState::InProgress("In progress".to_string()),
-            "unstarted" => MatchState::Unstarted("Unstarted".to_string()),
-            _ => MatchState::Unknown(name),
-        }
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct Team {
-    pub name: String,
-    pub short: String,
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct MatchResult {
-    pub game_wins: (u16, u16),
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct Event {
-    pub start_time: DateTime<Local>,
-    pub league_name: String,
-    pub block_name: String,
-    pub strategy: Strategy,
-    pub state: MatchState,
-    pub result: Option<MatchResult>,
-    pub teams: Vec<Team>,
-}
+use crate::clock::Clock;
+use crate::config::{
+    Config, DateRange, MaskScope, StateFormat, strings::Strings, utils::get_border_connections,
+};
+use crate::models::{Event, MatchState, StratType};
+use crate::resources;
 
 #[derive(Debug, Default)]
 pub struct ScheduleState {
@@ -105,13 +31,37 @@ pub struct ScheduleState {
     pub spoil_matches: bool,
     pub offset: usize,
     pub selected: Option<usize>,
+    // Set by jumps, the next render places the selection using `scroll_off`
+    jumped: bool,
+    // Id of the selected match as of the last render, to follow it across reloads
+    selected_match: Option<String>,
+    // Days collapsed to their date header, kept until the program exits
+    pub folded: HashSet<NaiveDate>,
 }
 
+const WATCHED_FILE: &str = "watched.json";
+
+// Events sort by start time, the match id tells apart matches starting at the same time.
+// Times are compared as instants and days are the local dates of them, so DST changes
+// neither reorder matches nor move them to another day
+type Key = (DateTime<Local>, String);
+
 #[derive(Debug)]
 pub struct Events {
     active: Vec<String>,
-    events: HashMap<String, Vec<Event>>,
-    config: Rc<Config>,
+    // Every fetched event and the league it was fetched for
+    events: BTreeMap<Key, (String, Event)>,
+    // Keys of the events of each league
+    leagues: HashMap<String, BTreeSet<Key>>,
+    // Days of the active events that are shown
+    pub date_range: DateRange,
+    // Ids of active matches starting within `conflict_window` of another
+    conflicts: HashSet<String>,
+    // Ids of matches marked as watched, kept across sessions
+    watched: HashSet<String>,
+    watched_path: PathBuf,
+    clock: Clock,
+    config: Arc<Config>,
 }
 
 impl ScheduleState {
@@ -123,17 +73,39 @@ impl ScheduleState {
     }
 
     pub fn select_today(&mut self, events: &Events) {
-        let today = Local::now();
+        let today = events.clock.now();
 
         debug!("active: {:?}", events.active);
-        let mut events: Vec<&Event> = events
-            .events
+        let events = events.view(self);
+
+        if events.is_empty() {
+            return;
+        }
+
+        let sel = events
             .iter()
-            .filter(|(key, _)| events.active.contains(key))
-            .flat_map(|(_, event_list)| event_list.iter())
-            .collect();
+            .position(|e| e.start_time >= today || matches!(e.state, MatchState::InProgress(_)));
 
-        events.sort_by_key(|event| event.start_time);
+        self.jump_to(sel.unwrap_or(events.len() - 1));
+        self.remember(&events);
+    }
+
+    // Keeps the cursor on the previously selected match if it is still shown,
+    // otherwise selects today
+    pub fn reselect_or_today(&mut self, events: &Events) {
+        let position = self
+            .selected_match
+            .as_ref()
+            .and_then(|id| events.view(self).iter().position(|e| &e.match_id == id));
+        match position {
+            Some(index) => self.selected = Some(index),
+            None => self.select_today(events),
+        }
+    }
+
+    // Selects the first match on or after the date, or the last match if there are none
+    pub fn select_date(&mut self, events: &Events, date: NaiveDate) {
+        let events = events.view(self);
 
         if events.is_empty() {
             return;
@@ -141,45 +113,211 @@ impl ScheduleState {
 
         let sel = events
             .iter()
-            .position(|e| e.start_time >= today || matches!(e.state, MatchState::InProgress(_)));
+            .position(|e| e.start_time.date_naive() >= date);
+
+        self.jump_to(sel.unwrap_or(events.len() - 1));
+        self.remember(&events);
+    }
+
+    // Selects the first upcoming or live match involving a favorite team
+    pub fn select_favorite(&mut self, events: &Events) {
+        let today = events.clock.now();
+        let config = &events.config;
+
+        let sel = events.view(self).iter().position(|e| {
+            (e.start_time >= today || matches!(e.state, MatchState::InProgress(_)))
+                && e.teams.iter().any(|t| config.is_favorite(&t.short))
+        });
+
+        if let Some(sel) = sel {
+            self.jump_to(sel);
+            self.remember(&events.view(self));
+        }
+    }
+
+    // Selects the next live match after the selection, wrapping around to the first
+    pub fn select_live(&mut self, events: &Events) -> bool {
+        let live: Vec<usize> = events
+            .view(self)
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| matches!(e.state, MatchState::InProgress(_)))
+            .map(|(i, _)| i)
+            .collect();
+        let next = live.iter().find(|&&i| Some(i) > self.selected);
+        match next.or(live.first()) {
+            Some(&index) => {
+                self.jump_to(index);
+                self.remember(&events.view(self));
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Selects the match if the schedule shows it
+    pub fn select_match(&mut self, events: &Events, match_id: &str) -> bool {
+        let position = events
+            .view(self)
+            .iter()
+            .position(|e| e.match_id == match_id);
+        if let Some(index) = position {
+            self.jump_to(index);
+            self.remember(&events.view(self));
+        }
+        position.is_some()
+    }
+
+    // Folds the day of the selected match, or unfolds it if it is folded already
+    pub fn toggle_fold(&mut self, events: &Events) {
+        let Some(date) = events
+            .selected_event(self)
+            .map(|e| e.start_time.date_naive())
+        else {
+            return;
+        };
+        if !self.folded.remove(&date) {
+            self.folded.insert(date);
+        }
+        // The selection stays on the day, which starts with its first match
+        let view = events.view(self);
+        self.selected = view.iter().position(|e| e.start_time.date_naive() == date);
+        self.remember(&view);
+    }
 
-        self.selected = Some(sel.unwrap_or(events.len() - 1));
-        self.offset = self.selected.unwrap_or_default();
+    fn jump_to(&mut self, index: usize) {
+        self.selected = Some(index);
+        self.offset = index;
+        self.jumped = true;
     }
 
-    pub fn scroll_up_by(&mut self, amount: u16) {
+    pub fn scroll_up_by(&mut self, events: &Events, amount: u16) {
         match self.selected {
             Some(sel) => self.selected = Some(sel.saturating_sub(amount as usize)),
             None => self.selected = Some(self.offset),
         }
+        self.clamp(events);
     }
 
-    pub fn scroll_down_by(&mut self, amount: u16) {
+    pub fn scroll_down_by(&mut self, events: &Events, amount: u16) {
         match self.selected {
             Some(sel) => self.selected = Some(sel.saturating_add(amount as usize)),
             None => self.selected = Some(self.offset),
         }
+        self.clamp(events);
+    }
+
+    // Keeps the selection on a shown match, so it never points past the end of the view
+    fn clamp(&mut self, events: &Events) {
+        let view = events.view(self);
+        if self.selected.is_some_and(|s| s >= view.len()) {
+            self.selected = view.len().checked_sub(1);
+        }
+        self.remember(&view);
+    }
+
+    // Indices move when matches are added or filtered, so every change of the selection also
+    // notes its match, which `reselect_or_today` finds again after the next change of the data
+    fn remember(&mut self, view: &[&Event]) {
+        self.selected_match = self
+            .selected
+            .and_then(|i| view.get(i))
+            .map(|e| e.match_id.to_string())
+            .filter(|id| !id.is_empty());
     }
 }
 
 impl Events {
-    pub fn new(config: Rc<Config>) -> Self {
+    pub fn new(config: Arc<Config>) -> Self {
+        let watched_path = config.data_dir.join(WATCHED_FILE);
+        let watched = resources::load_json(&watched_path);
         Self {
             active: Vec::new(),
-            events: HashMap::new(),
-            config: config,
+            events: BTreeMap::new(),
+            leagues: HashMap::new(),
+            date_range: config.date_range,
+            conflicts: HashSet::new(),
+            watched,
+            watched_path,
+            clock: Clock::default(),
+            config,
         }
     }
 
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+
+    pub fn is_watched(&self, match_id: &str) -> bool {
+        self.watched.contains(match_id)
+    }
+
+    pub fn set_watched(&mut self, match_id: &str, watched: bool) -> std::io::Result<()> {
+        match watched {
+            true => self.watched.insert(match_id.to_string()),
+            false => self.watched.remove(match_id),
+        };
+        resources::save_json(&self.watched_path, &self.watched)
+    }
+
+    // Completed active matches not marked as watched, newest first. The date range is ignored,
+    // as VODs are watched long after the match
+    pub fn vod_queue(&self, favorites_only: bool) -> Vec<Event> {
+        let mut queue: Vec<Event> = self
+            .active_events()
+            .filter(|e| matches!(e.state, MatchState::Completed(_)))
+            .filter(|e| !e.match_id.is_empty() && !self.is_watched(&e.match_id))
+            .filter(|e| {
+                !favorites_only || e.teams.iter().any(|t| self.config.is_favorite(&t.short))
+            })
+            .cloned()
+            .collect();
+        queue.reverse();
+        queue
+    }
+
+    // Replaces the events of a league, a match listed twice is only kept once
     pub fn add_events(&mut self, slug: String, events: Vec<Event>) {
-        self.events.insert(slug, events);
-        debug!("Inserted new events: {:?}", self.events);
+        for key in self.leagues.remove(&slug).unwrap_or_default() {
+            // Kept while another league still lists it
+            if !self.leagues.values().any(|keys| keys.contains(&key)) {
+                self.events.remove(&key);
+            }
+        }
+        let mut keys = BTreeSet::new();
+        for (i, event) in events.into_iter().enumerate() {
+            // Cached events from older versions have no match id
+            let id = match event.match_id.is_empty() {
+                true => format!("{}/{}", slug, i),
+                false => event.match_id.to_string(),
+            };
+            let key = (event.start_time, id);
+            keys.insert(key.clone());
+            self.events.insert(key, (slug.to_string(), event));
+        }
+        debug!("Inserted {} events for '{}'", keys.len(), slug);
+        self.leagues.insert(slug, keys);
+        self.update_owners();
+        self.update_conflicts();
+    }
+
+    // The fetched matches of a league, active or not, sorted by start time
+    pub fn league_events(&self, slug: &str) -> Vec<&Event> {
+        self.leagues
+            .get(slug)
+            .into_iter()
+            .flatten()
+            .filter_map(|key| self.events.get(key))
+            .map(|(_, event)| event)
+            .collect()
     }
 
     pub fn set_active(&mut self, slug: String) {
         info!("Inserting new active: '{}'", slug);
         if !self.active.contains(&slug) {
             self.active.push(slug);
+            self.update_owners();
+            self.update_conflicts();
         }
     }
 
@@ -187,12 +325,233 @@ impl Events {
         info!("Removing active: '{}'", slug);
         if let Some(pos) = self.active.iter().position(|x| x == slug) {
             self.active.remove(pos);
+            self.update_owners();
+            self.update_conflicts();
+        }
+    }
+
+    // Series wins of each team of `event` in the completed matches between them, of every
+    // fetched league, and the number of those matches. `event` itself does not count
+    pub fn head_to_head(&self, event: &Event) -> (u16, u16, usize) {
+        let (code0, code1) = (&event.teams[0].short, &event.teams[1].short);
+        let mut record = (0, 0, 0);
+        for (_, other) in self.events.values() {
+            if !event.match_id.is_empty() && other.match_id == event.match_id {
+                continue;
+            }
+            let (MatchState::Completed(_), Some(result)) = (&other.state, &other.result) else {
+                continue;
+            };
+            let (wins0, wins1) = match (&other.teams[0].short, &other.teams[1].short) {
+                (a, b) if a == code0 && b == code1 => result.game_wins,
+                (a, b) if a == code1 && b == code0 => (result.game_wins.1, result.game_wins.0),
+                _ => continue,
+            };
+            record.2 += 1;
+            match wins0.cmp(&wins1) {
+                std::cmp::Ordering::Greater => record.0 += 1,
+                std::cmp::Ordering::Less => record.1 += 1,
+                std::cmp::Ordering::Equal => {}
+            }
+        }
+        record
+    }
+
+    // Matches of the active leagues today and how many are live, and the next start after now,
+    // in any date range
+    pub fn today(&self, now: DateTime<Local>) -> (usize, usize, Option<DateTime<Local>>) {
+        (
+            self.events_on(now.date_naive()).count(),
+            self.live().count(),
+            self.next_unstarted(now).map(|e| e.start_time),
+        )
+    }
+
+    // Name, shown and fetched matches of each active league, in the order they were activated
+    pub fn league_counts(&self, shown: &[&Event]) -> Vec<(String, usize, usize)> {
+        self.active
+            .iter()
+            .filter_map(|slug| {
+                let keys = self.leagues.get(slug)?;
+                let name = &self.events.get(keys.first()?)?.1.league_name;
+                let count = shown.iter().filter(|e| &e.league_name == name).count();
+                Some((name.clone(), count, keys.len()))
+            })
+            .collect()
+    }
+
+    // The live match, or the next match to start, of the active leagues, in any date range
+    pub fn next_event(&self) -> Option<&Event> {
+        let now = self.clock.now();
+        self.live()
+            .next()
+            .or_else(|| self.in_range((now, String::new())..).next())
+    }
+
+    pub fn selected_event(&self, state: &ScheduleState) -> Option<&Event> {
+        state
+            .selected
+            .and_then(|i| self.view(state).get(i).copied())
+    }
+
+    // The queries below cover the active leagues in any date range, in start time order
+
+    pub fn live(&self) -> impl Iterator<Item = &Event> {
+        self.active_events()
+            .filter(|e| matches!(e.state, MatchState::InProgress(_)))
+    }
+
+    // Matches starting on the local date
+    pub fn events_on(&self, date: NaiveDate) -> impl Iterator<Item = &Event> {
+        self.active_events()
+            .filter(move |e| e.start_time.date_naive() == date)
+    }
+
+    // The first match yet to start after `now`
+    pub fn next_unstarted(&self, now: DateTime<Local>) -> Option<&Event> {
+        self.in_range((now, String::new())..)
+            .find(|e| e.start_time > now && matches!(e.state, MatchState::Unstarted(_)))
+    }
+
+    fn in_range(&self, range: impl RangeBounds<Key>) -> impl Iterator<Item = &Event> {
+        self.events
+            .range(range)
+            .filter(|(_, (league, _))| self.active.contains(league))
+            .map(|(_, (_, event))| event)
+    }
+
+    // Active leagues that currently have a match in progress
+    pub fn live_leagues(&self) -> Vec<String> {
+        let mut live: Vec<String> = Vec::new();
+        for (league, event) in self.events.values() {
+            if matches!(event.state, MatchState::InProgress(_))
+                && self.active.contains(league)
+                && !live.contains(league)
+            {
+                live.push(league.to_string());
+            }
+        }
+        live
+    }
+
+    // A match listed by several leagues belongs to one of them that is active, if any is, so
+    // it stays shown until the last of them is deactivated
+    fn update_owners(&mut self) {
+        for (key, (owner, _)) in self.events.iter_mut() {
+            let lists = |slug: &String| self.leagues.get(slug).is_some_and(|k| k.contains(key));
+            if self.active.contains(owner) && lists(owner) {
+                continue;
+            }
+            let listing: Vec<&String> = self.leagues.keys().filter(|slug| lists(slug)).collect();
+            match listing.iter().find(|slug| self.active.contains(slug)) {
+                Some(slug) => *owner = slug.to_string(),
+                // The match was dropped by its league but is still listed by another
+                None if !lists(owner) => {
+                    if let Some(slug) = listing.first() {
+                        *owner = slug.to_string();
+                    }
+                }
+                None => {}
+            }
         }
     }
 
+    // Marks matches that start close to a later one, events are sorted by start time so each
+    // is only compared with the matches after it that start within the window
+    fn update_conflicts(&mut self) {
+        let mut conflicts = HashSet::new();
+        let window = self
+            .config
+            .conflict_window
+            .and_then(|w| chrono::Duration::from_std(w).ok());
+        if let Some(window) = window {
+            let events: Vec<&Event> = self.active_events().collect();
+            for (i, event) in events.iter().enumerate() {
+                for other in events[i + 1..]
+                    .iter()
+                    .take_while(|o| o.start_time - event.start_time <= window)
+                {
+                    conflicts.insert(event.match_id.to_string());
+                    conflicts.insert(other.match_id.to_string());
+                }
+            }
+        }
+        // Cached events from older versions have no id to tell them apart
+        conflicts.remove("");
+        self.conflicts = conflicts;
+    }
+
+    pub fn active_events(&self) -> impl Iterator<Item = &Event> {
+        self.events
+            .values()
+            .filter(|(league, _)| self.active.contains(league))
+            .map(|(_, event)| event)
+    }
+
+    // The active events that pass every filter, in order
+    pub fn filtered(&self) -> Vec<&Event> {
+        // Once per call instead of once per event
+        let (from, to) = self.date_range.bounds(self.clock.now().date_naive());
+        self.active_events()
+            .filter(|e| {
+                let date = e.start_time.date_naive();
+                from.is_none_or(|from| date >= from) && to.is_none_or(|to| date <= to)
+            })
+            .collect()
+    }
+
+    // The events as the schedule shows them, where folded days are left with their first
+    // match. Selection indices point into this list, so filters only belong in `filtered`
+    pub fn view(&self, state: &ScheduleState) -> Vec<&Event> {
+        let mut shown: Vec<&Event> = Vec::new();
+        for event in self.filtered() {
+            let date = event.start_time.date_naive();
+            if state.folded.contains(&date)
+                && shown
+                    .last()
+                    .is_some_and(|last| last.start_time.date_naive() == date)
+            {
+                continue;
+            }
+            shown.push(event);
+        }
+        shown
+    }
+
+    // Offset that leaves `scroll_off` matches above the selection, or centers it
+    fn get_jump_offset(
+        &self,
+        events: &[&Event],
+        folded: &HashSet<NaiveDate>,
+        selected: usize,
+        max_height: usize,
+    ) -> usize {
+        let selected = selected.min(events.len().saturating_sub(1));
+        if let Some(scroll_off) = self.config.scroll_off {
+            return selected.saturating_sub(scroll_off);
+        }
+
+        let mut offset = selected;
+        let mut height = DATE_HEIGHT + event_height(folded, events[offset]);
+        while offset > 0 {
+            let mut added = event_height(folded, events[offset - 1]);
+            if events[offset - 1].start_time.date_naive() != events[offset].start_time.date_naive()
+            {
+                added += DATE_HEIGHT;
+            }
+            if height + added > max_height / 2 {
+                break;
+            }
+            height += added;
+            offset -= 1;
+        }
+        offset
+    }
+
     fn get_events_bounds(
         &self,
         events: &Vec<&Event>,
+        folded: &HashSet<NaiveDate>,
         selected: Option<usize>,
         offset: usize,
         max_height: usize,
@@ -207,20 +566,21 @@ impl Events {
         let mut last_date: Option<NaiveDate> = None;
 
         for event in events.iter().skip(offset) {
-            if height_from_offset + EVENT_HEIGHT > max_height {
+            let height = event_height(folded, event);
+            if height_from_offset + height > max_height {
                 break;
             }
 
             let current_date: NaiveDate = event.start_time.date_naive();
 
             if Some(current_date) != last_date {
-                if height_from_offset + DATE_HEIGHT + EVENT_HEIGHT > max_height {
+                if height_from_offset + DATE_HEIGHT + height > max_height {
                     break;
                 }
                 height_from_offset += DATE_HEIGHT;
                 last_date = Some(current_date);
             }
-            height_from_offset += EVENT_HEIGHT;
+            height_from_offset += height;
             last_visible_index += 1;
         }
 
@@ -234,13 +594,14 @@ impl Events {
                 last_date = Some(date);
             }
 
-            height_from_offset = height_from_offset.saturating_add(EVENT_HEIGHT);
+            height_from_offset =
+                height_from_offset.saturating_add(event_height(folded, events[last_visible_index]));
             last_visible_index += 1;
 
             while height_from_offset > max_height {
                 let first_date = events[first_visible_index].start_time.date_naive();
 
-                let second_last_date = if first_visible_index + 1 <= last_visible_index {
+                let second_last_date = if first_visible_index < last_visible_index {
                     Some(events[first_visible_index + 1].start_time.date_naive())
                 } else {
                     None
@@ -250,7 +611,8 @@ impl Events {
                     height_from_offset = height_from_offset.saturating_sub(DATE_HEIGHT);
                 }
 
-                height_from_offset = height_from_offset.saturating_sub(EVENT_HEIGHT);
+                height_from_offset = height_from_offset
+                    .saturating_sub(event_height(folded, events[first_visible_index]));
                 first_visible_index += 1;
             }
         }
@@ -262,7 +624,8 @@ impl Events {
                 height_from_offset = height_from_offset.saturating_add(DATE_HEIGHT);
             }
 
-            height_from_offset = height_from_offset.saturating_add(EVENT_HEIGHT);
+            height_from_offset = height_from_offset
+                .saturating_add(event_height(folded, events[first_visible_index - 1]));
             first_visible_index -= 1;
 
             while height_from_offset > max_height {
@@ -271,17 +634,149 @@ impl Events {
                 if last_date != events[last_visible_index - 1].start_time.date_naive() {
                     height_from_offset = height_from_offset.saturating_sub(DATE_HEIGHT);
                 }
-                height_from_offset = height_from_offset.saturating_sub(EVENT_HEIGHT);
+                height_from_offset = height_from_offset
+                    .saturating_sub(event_height(folded, events[last_visible_index]));
             }
         }
 
         (first_visible_index, last_visible_index)
     }
+
+    // The counts on the right of the header row and the markers on the left, the markers
+    // only if they fit beside the counts
+    fn render_header(&self, events: &[&Event], area: Rect, buf: &mut Buffer) {
+        let styles = &self.config.style;
+        let mut content = format!("({}/{})", events.len(), self.events.len());
+        // Ex: "LPL 300/310  LCK 50/60  (350/370)", the total alone if it does not fit. League
+        // names may have characters wider than a column
+        if self.config.league_counts {
+            let mut counts = String::new();
+            for (name, shown, total) in self.league_counts(events) {
+                let _ = write!(counts, "{} {}/{}  ", name, shown, total);
+            }
+            if area.width as usize >= Line::from(counts.as_str()).width() + content.len() {
+                content.insert_str(0, &counts);
+            }
+        }
+        let content = Line::from(content).style(styles.counts());
+        // Lines style all of their area, which would restyle the border between the texts
+        let width = content.width() as u16;
+        if area.width >= width {
+            let counts_area = Rect {
+                x: area.right() - width,
+                width,
+                ..area
+            };
+            content.render_ref(counts_area, buf);
+        }
+
+        let markers = self.markers();
+        if !markers.is_empty() && area.width as usize >= content.width() + markers.len() + 2 {
+            let markers_area = Rect {
+                width: markers.len() as u16,
+                ..area
+            };
+            Line::from(markers)
+                .style(styles.counts())
+                .render_ref(markers_area, buf);
+        }
+    }
+
+    // Ex: "(offline) (insecure)", settings the schedule should not be read without
+    fn markers(&self) -> String {
+        [
+            // Nothing is fetched this session, so the schedule may be outdated
+            (self.config.offline, "(offline)"),
+            // Someone intercepting TLS could change what is shown
+            (self.config.danger_accept_invalid_certs, "(insecure)"),
+        ]
+        .into_iter()
+        .filter_map(|(on, marker)| on.then_some(marker))
+        .collect::<Vec<_>>()
+        .join(" ")
+    }
+}
+
+// Counts and scores are small, so they rarely need a string of their own
+fn number(n: u16) -> Cow<'static, str> {
+    const DIGITS: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+    match DIGITS.get(n as usize) {
+        Some(digit) => Cow::Borrowed(digit),
+        None => Cow::Owned(n.to_string()),
+    }
+}
+
+// The games each team has won out of the wins the series needs, Ex: "●●○ Bo5 2-1 ●○○"
+fn series_progress(event: &Event) -> Option<String> {
+    let (StratType::BestOf(_), Some(result)) = (&event.strategy.strat_type, &event.result) else {
+        return None;
+    };
+    let needed = event.strategy.count / 2 + 1;
+    let dots = |wins: u16| {
+        let wins = wins.min(needed) as usize;
+        "●".repeat(wins) + &"○".repeat(needed as usize - wins)
+    };
+    let (wins0, wins1) = result.game_wins;
+    Some(format!(
+        "{} Bo{} {}-{} {}",
+        dots(wins0),
+        event.strategy.count,
+        wins0,
+        wins1,
+        dots(wins1)
+    ))
+}
+
+// The state column as set by `state_format`, states without a glyph are named
+fn state_label<'a>(config: &'a Config, state: &'a MatchState) -> Cow<'a, str> {
+    let glyph = match state {
+        MatchState::Completed(_) => Some(&config.state_glyphs[0]),
+        MatchState::InProgress(_) => Some(&config.state_glyphs[1]),
+        MatchState::Unstarted(_) => Some(&config.state_glyphs[2]),
+        MatchState::Unknown(_) => None,
+    };
+    let text = config.strings.state(state);
+    match (config.state_format, glyph) {
+        (StateFormat::Glyph, Some(glyph)) => Cow::Borrowed(glyph),
+        (StateFormat::Both, Some(glyph)) => Cow::Owned(format!("{} {}", glyph, text)),
+        _ => Cow::Borrowed(text),
+    }
+}
+
+// The game score of a match in progress, Ex: "1-1"
+fn live_score(event: &Event) -> Option<String> {
+    match (&event.state, &event.result) {
+        (MatchState::InProgress(_), Some(result)) => {
+            Some(format!("{}-{}", result.game_wins.0, result.game_wins.1))
+        }
+        _ => None,
+    }
+}
+
+// Wraps right aligned `text` in an OSC 8 hyperlink. Ratatui miscalculates the width of
+// escape sequences, so the link is written in 2 character chunks, each in a single cell.
+fn render_hyperlink(area: Rect, text: &str, url: &str, buf: &mut Buffer) {
+    let chars: Vec<char> = text.chars().collect();
+    let width = (chars.len() as u16).min(area.width);
+    let start = area.right() - width;
+    for (i, chunk) in chars[chars.len() - width as usize..].chunks(2).enumerate() {
+        let chunk: String = chunk.iter().collect();
+        let hyperlink = format!("\x1B]8;;{}\x07{}\x1B]8;;\x07", url, chunk);
+        buf[(start + i as u16 * 2, area.y)].set_symbol(&hyperlink);
+    }
 }
 
 const DATE_HEIGHT: usize = 2;
 const EVENT_HEIGHT: usize = 2;
 
+// Folded days only show their date header, with a summary of the day
+fn event_height(folded: &HashSet<NaiveDate>, event: &Event) -> usize {
+    match folded.contains(&event.start_time.date_naive()) {
+        true => 0,
+        false => EVENT_HEIGHT,
+    }
+}
+
 impl StatefulWidgetRef for &Events {
     type State = ScheduleState;
 
@@ -292,15 +787,11 @@ impl StatefulWidgetRef for &Events {
 
         let styles = &self.config.style;
 
-        let mut events: Vec<&Event> = self
-            .events
-            .iter()
-            .filter(|(slug, _)| self.active.contains(slug))
-            .flat_map(|(_, events)| events.iter())
-            .collect();
+        let events = self.view(state);
 
         let inner_area = {
-            if let (Some(block), Some(set)) = (styles.border, styles.border_set) {
+            let border = styles.schedule_border();
+            if let (Some(block), Some(set)) = (border, get_border_connections(border)) {
                 let border_style = if state.focused {
                     styles.highlight.bg(Color::Reset)
                 } else {
@@ -321,7 +812,7 @@ impl StatefulWidgetRef for &Events {
                         x: area.left(),
                         y: area.top() + 2,
                         width: area.width,
-                        height: 1 as u16,
+                        height: 1,
                     };
 
                     let title_line = Line::from(format!(
@@ -333,37 +824,18 @@ impl StatefulWidgetRef for &Events {
                     .style(border_style);
                     title_line.render_ref(title_area, buf);
 
-                    let total_events: Vec<&Event> = self
-                        .events
-                        .iter()
-                        .flat_map(|(_, events)| events.iter())
-                        .collect();
-
-                    let content = format!("({}/{})", events.len(), total_events.len());
-
-                    if area.width as usize >= content.len() + 4 {
-                        let showing_area: Rect = Rect {
-                            x: title_area.x + area.width.saturating_sub(content.len() as u16 + 2),
-                            y: title_area.y,
-                            width: title_area.width.saturating_sub(4).min(content.len() as u16),
-                            height: 1 as u16,
-                        };
-
-                        let showing_header =
-                            Line::from(content).right_aligned().style(styles.highlight);
-                        showing_header.render_ref(showing_area, buf);
-                    }
+                    self.render_header(&events, title_area.inner(Margin::new(2, 0)), buf);
 
                     let title_area: Rect = Rect {
                         x: area.left() + 1,
                         y: area.top() + 1,
                         width: area.width.saturating_sub(2),
-                        height: 1 as u16,
+                        height: 1,
                     };
 
-                    let title = Line::from("Schedule")
+                    let title = Line::from(self.config.strings.schedule.as_str())
                         .centered()
-                        .style(styles.highlight.bg(Color::Reset));
+                        .style(styles.title());
                     title.render_ref(title_area, buf);
 
                     inner.y += 2;
@@ -373,6 +845,15 @@ impl StatefulWidgetRef for &Events {
                     inner.height = inner.height.saturating_sub(1);
                 }
                 inner
+            } else if (!self.markers().is_empty() || self.config.league_counts) && area.height >= 2
+            {
+                // Without a border the header only gets a row of its own when it has more than
+                // the total
+                let [header, rest] =
+                    Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+                let padding = Margin::new(self.config.padding, 0);
+                self.render_header(&events, header.inner(padding), buf);
+                rest
             } else {
                 area
             }
@@ -382,8 +863,10 @@ impl StatefulWidgetRef for &Events {
         }
 
         Clear.render(inner_area, buf);
-
-        events.sort_by_key(|event| event.start_time);
+        let inner_area = inner_area.inner(Margin::new(self.config.padding, 0));
+        if inner_area.is_empty() {
+            return;
+        }
 
         if events.is_empty() {
             state.selected = None;
@@ -397,39 +880,95 @@ impl StatefulWidgetRef for &Events {
 
         let max_height = inner_area.height as usize;
 
-        let (first_visible_index, _) =
-            self.get_events_bounds(&events, state.selected, state.offset, max_height);
+        if state.jumped
+            && let Some(selected) = state.selected
+        {
+            state.offset = self.get_jump_offset(&events, &state.folded, selected, max_height);
+        }
+        state.jumped = false;
+
+        let (first_visible_index, _) = self.get_events_bounds(
+            &events,
+            &state.folded,
+            state.selected,
+            state.offset,
+            max_height,
+        );
+
+        state.selected_match = state
+            .selected
+            .map(|s| events[s].match_id.to_string())
+            .filter(|id| !id.is_empty());
 
         state.offset = first_visible_index;
 
         let mut current_height: u16 = 0;
         let mut last_date: Option<NaiveDate> = None;
 
+        let strings = &self.config.strings;
+        let vs = format!(" {} ", strings.vs);
+        let state_width = [
+            MatchState::Completed(String::new()),
+            MatchState::InProgress(String::new()),
+            MatchState::Unstarted(String::new()),
+        ]
+        .iter()
+        .map(|s| state_label(&self.config, s).chars().count())
+        .max()
+        .unwrap_or_default();
+        let state_width = match self.config.state_format {
+            StateFormat::Text => state_width.max(11),
+            _ => state_width,
+        };
+        // Room for the score in front of the state, only while a shown match is in progress
+        let score_width = match state.spoil_results {
+            true => events
+                .iter()
+                .filter_map(|e| live_score(e))
+                .map(|s| s.chars().count() + 1)
+                .max()
+                .unwrap_or_default(),
+            false => 0,
+        };
+        let state_width = (state_width + score_width) as u16;
+
         let hor_layout = Layout::horizontal([
-            Constraint::Length(3),  // - or *
-            Constraint::Length(5),  // time
-            Constraint::Min(4),     // team0
-            Constraint::Length(4),  // vs
-            Constraint::Min(4),     // team1
-            Constraint::Length(11), // empty
+            Constraint::Length(3),                         // - or *
+            Constraint::Length(5),                         // time
+            Constraint::Min(4),                            // team0
+            Constraint::Length(vs.chars().count() as u16), // vs
+            Constraint::Min(4),                            // team1
+            Constraint::Length(state_width),               // state
         ])
+        .spacing(self.config.column_spacing)
         .split(inner_area);
 
-        let hor = if let Some(set) = styles.border_set {
+        let hor = if let Some(set) = get_border_connections(styles.schedule_border()) {
             set.horizontal
         } else {
             line::HORIZONTAL
         };
 
         let date_header =
-            Line::from(format!("{}", hor.repeat(inner_area.width as usize))).style(styles.default);
+            Line::from(hor.repeat(inner_area.width as usize).to_string()).style(styles.separator());
 
+        // Matches of each folded day, for their summaries
+        let mut day_counts: HashMap<NaiveDate, usize> = HashMap::new();
+        if !state.folded.is_empty() {
+            for event in self.filtered() {
+                *day_counts.entry(event.start_time.date_naive()).or_default() += 1;
+            }
+        }
+
+        // Reused for every row, instead of allocating a string per row
+        let mut time = String::new();
+        let today_year = self.clock.now().year();
         for (i, event) in events.iter().enumerate().skip(state.offset) {
             let date: NaiveDate = event.start_time.date_naive();
 
             // If new date, render date header
             if Some(date) != last_date {
-                if last_date != None {
+                if last_date.is_some() {
                     if current_height as usize + 1 > max_height {
                         break;
                     }
@@ -437,10 +976,25 @@ impl StatefulWidgetRef for &Events {
                         x: inner_area.left(),
                         y: inner_area.top() + current_height,
                         width: inner_area.width,
-                        height: 1 as u16,
+                        height: 1,
                     };
 
-                    date_header.render_ref(date_area, buf);
+                    let new_month = last_date.is_some_and(|last| {
+                        (last.year(), last.month()) != (date.year(), date.month())
+                    });
+                    if self.config.month_headers && new_month {
+                        let month = format!(" {} ", event.start_time.format("%B %Y"));
+                        let rest =
+                            (inner_area.width as usize).saturating_sub(month.chars().count() + 2);
+                        Line::from(vec![
+                            Span::styled(hor.repeat(2), styles.separator()),
+                            Span::styled(month, styles.date()),
+                            Span::styled(hor.repeat(rest), styles.separator()),
+                        ])
+                        .render(date_area, buf);
+                    } else {
+                        date_header.render_ref(date_area, buf);
+                    }
                     current_height += 1;
                 }
 
@@ -454,12 +1008,20 @@ impl StatefulWidgetRef for &Events {
                 {
                     styles.selected
                 } else {
-                    styles.highlight.bg(Color::Reset)
+                    styles.date()
                 };
 
-                let date_line = Line::from(event.start_time.format("%A - %d %B").to_string())
-                    .right_aligned()
-                    .style(style);
+                let mut date_text = match date.year() == today_year {
+                    true => event.start_time.format("%A - %d %B").to_string(),
+                    false => event.start_time.format("%A - %d %B %Y").to_string(),
+                };
+                let folded = state.folded.contains(&date);
+                if folded {
+                    let count = day_counts.get(&date).copied().unwrap_or_default();
+                    let hidden = Strings::count(count, &strings.hidden_one, &strings.hidden);
+                    let _ = write!(date_text, " — {}", hidden);
+                }
+                let date_line = Line::from(date_text).right_aligned().style(style);
 
                 let date_area: Rect = Rect {
                     x: inner_area.left()
@@ -468,18 +1030,21 @@ impl StatefulWidgetRef for &Events {
                             .saturating_sub(date_line.width() as u16 + 1)),
                     y: inner_area.top() + current_height,
                     width: date_line.width().min(inner_area.width as usize) as u16,
-                    height: 1 as u16,
+                    height: 1,
                 };
                 current_height += 1;
                 date_line.render(date_area, buf);
                 last_date = Some(date);
+                if folded {
+                    continue;
+                }
             }
 
             if current_height as usize + 1 > max_height {
                 break;
             }
 
-            let event_top_layout: Rc<[Rect]> = hor_layout
+            let event_top_layout: Vec<Rect> = hor_layout
                 .iter()
                 .map(|r| Rect {
                     x: r.x,
@@ -489,18 +1054,38 @@ impl StatefulWidgetRef for &Events {
                 })
                 .collect();
 
-            let style = if state.selected.is_some_and(|s| s == i) && state.focused {
+            let is_highlighted = state.selected.is_some_and(|s| s == i) && state.focused;
+            let style = if is_highlighted {
                 styles.highlight
             } else {
                 styles.default
             };
 
-            let (mut team0, mut team1) =
-                if event_top_layout[2].width > 30 && event_top_layout[4].width > 30 {
-                    (event.teams[0].name.clone(), event.teams[1].name.clone())
-                } else {
-                    (event.teams[0].short.clone(), event.teams[1].short.clone())
-                };
+            // Element styles keep the highlight background of the selected row
+            let element_style = |element: Option<Style>| match element {
+                Some(s) if is_highlighted => s.bg(styles.highlight.bg.unwrap_or(Color::Reset)),
+                Some(s) => s,
+                None => style,
+            };
+            let state_style = match event.state {
+                MatchState::InProgress(_) => element_style(styles.in_progress.or(styles.state)),
+                _ => element_style(styles.state),
+            };
+            let info_style = element_style(styles.info);
+            let conflict = self.conflicts.contains(&event.match_id);
+            let time_style = match conflict {
+                true => element_style(styles.conflict),
+                false => style,
+            };
+
+            let (name0, name1) = if event_top_layout[2].width > 30 && event_top_layout[4].width > 30
+            {
+                (&event.teams[0].name, &event.teams[1].name)
+            } else {
+                (&event.teams[0].short, &event.teams[1].short)
+            };
+            let mut team0 = Line::from(name0.as_str());
+            let mut team1 = Line::from(name1.as_str());
 
             let mut style0 = style;
             let mut style1 = style;
@@ -508,7 +1093,8 @@ impl StatefulWidgetRef for &Events {
             if state.spoil_results && !matches!(event.state, MatchState::Unstarted(_)) {
                 (team0, team1) = match &event.result {
                     Some(res) => {
-                        if matches!(event.state, MatchState::Completed(_)) {
+                        let completed = matches!(event.state, MatchState::Completed(_));
+                        if completed {
                             if res.game_wins.0 > res.game_wins.1 {
                                 if let Some(style_winner) = styles.winner {
                                     style0 = style_winner;
@@ -525,27 +1111,55 @@ impl StatefulWidgetRef for &Events {
                                 }
                             }
                         }
-                        (
-                            format!("{} - {}", res.game_wins.0, team0),
-                            format!("{} - {}", team1, res.game_wins.1),
-                        )
+                        let mut line0 = Line::from(vec![
+                            Span::raw(number(res.game_wins.0)),
+                            Span::raw(" - "),
+                            Span::raw(name0.as_str()),
+                        ]);
+                        let mut line1 = Line::from(vec![
+                            Span::raw(name1.as_str()),
+                            Span::raw(" - "),
+                            Span::raw(number(res.game_wins.1)),
+                        ]);
+                        // On the outer side, so the names stay next to "vs"
+                        if let Some(glyph) = self.config.winner_glyph.as_deref()
+                            && completed
+                        {
+                            if res.game_wins.0 > res.game_wins.1 {
+                                line0.spans.insert(0, Span::raw(format!("{} ", glyph)));
+                            } else if res.game_wins.1 > res.game_wins.0 {
+                                line1.spans.push(Span::raw(format!(" {}", glyph)));
+                            }
+                        }
+                        (line0, line1)
                     }
                     None => (team0, team1),
                 };
             }
 
-            if state.selected.is_some_and(|s| s == i) && state.focused {
-                style0.bg = styles.highlight.bg;
-                style1.bg = styles.highlight.bg;
-            }
-
-            if !state.spoil_matches && matches!(event.state, MatchState::Unstarted(_)) {
+            let masked = !state.spoil_matches && matches!(event.state, MatchState::Unstarted(_));
+            let mask = self.config.spoiler_mask.as_str();
+            let scope = self.config.spoiler_mask_scope;
+            if masked {
                 if event.teams[0].name != "TBD" {
-                    team0 = "???".to_string();
+                    team0 = Line::from(mask);
                 }
                 if event.teams[1].name != "TBD" {
-                    team1 = "???".to_string();
+                    team1 = Line::from(mask);
+                }
+            } else if let Some(style_favorite) = styles.favorite {
+                // Only mark favorites when the team is not hidden, to avoid spoiling matches
+                if self.config.is_favorite(&event.teams[0].short) {
+                    style0 = style0.patch(style_favorite);
                 }
+                if self.config.is_favorite(&event.teams[1].short) {
+                    style1 = style1.patch(style_favorite);
+                }
+            }
+
+            if is_highlighted {
+                style0.bg = styles.highlight.bg;
+                style1.bg = styles.highlight.bg;
             }
 
             Text::from(if state.selected.is_some_and(|s| s == i) {
@@ -555,8 +1169,14 @@ impl StatefulWidgetRef for &Events {
             })
             .style(style)
             .render(event_top_layout[0], buf);
-            Text::from(event.start_time.format("%H:%M").to_string())
-                .style(style)
+            time.clear();
+            if masked && scope >= MaskScope::Time {
+                time.push_str(mask);
+            } else {
+                let _ = write!(time, "{}", event.start_time.format("%H:%M"));
+            }
+            Text::from(time.as_str())
+                .style(time_style)
                 .add_modifier(Modifier::BOLD)
                 .left_aligned()
                 .render(event_top_layout[1], buf);
@@ -564,7 +1184,7 @@ impl StatefulWidgetRef for &Events {
                 .style(style0)
                 .right_aligned()
                 .render(event_top_layout[2], buf);
-            Text::from(" vs ")
+            Text::from(vs.as_str())
                 .style(style)
                 .centered()
                 .render(event_top_layout[3], buf);
@@ -572,10 +1192,28 @@ impl StatefulWidgetRef for &Events {
                 .style(style1)
                 .left_aligned()
                 .render(event_top_layout[4], buf);
-            Text::from(event.state.get_string())
-                .style(style)
+            let label = state_label(&self.config, &event.state);
+            let mut state_line = Line::from(label.as_ref()).style(state_style);
+            if state.spoil_results
+                && let Some(score) = live_score(event)
+            {
+                state_line = Line::from(vec![
+                    Span::styled(
+                        score,
+                        element_style(styles.live_score.or(styles.in_progress)),
+                    ),
+                    Span::styled(" ", state_style),
+                    Span::styled(label.as_ref(), state_style),
+                ]);
+            }
+            Text::from(state_line)
                 .right_aligned()
                 .render(event_top_layout[5], buf);
+            if self.config.hyperlinks
+                && let Some(url) = event.link_url()
+            {
+                render_hyperlink(event_top_layout[5], &label, &url, buf);
+            }
             current_height += 1;
 
             if current_height as usize + 1 > max_height {
@@ -589,25 +1227,149 @@ impl StatefulWidgetRef for &Events {
                 height: 1,
             };
 
-            Text::from(format!(
-                "   {} {}",
-                event.strategy.strat_type.get_string(),
-                event.strategy.count
-            ))
-            .left_aligned()
-            .style(style)
-            .render(event_low_area, buf);
+            // The format and block can tell a final apart from other matches
+            let series = match event.state {
+                MatchState::InProgress(_) | MatchState::Completed(_) if state.spoil_results => {
+                    series_progress(event)
+                }
+                _ => None,
+            };
+            let indent = match conflict {
+                true => Span::styled(" ! ", time_style),
+                false => Span::raw("   "),
+            };
+            let (mut format, block) = if masked && scope == MaskScope::Row {
+                (Line::from(vec![indent.clone(), Span::raw(mask)]), mask)
+            } else if let Some(series) = series {
+                (
+                    Line::from(vec![
+                        indent.clone(),
+                        Span::styled(series, element_style(styles.series)),
+                    ]),
+                    event.block_name.as_str(),
+                )
+            } else {
+                (
+                    Line::from(vec![
+                        indent.clone(),
+                        Span::raw(strings.strategy(&event.strategy.strat_type)),
+                        Span::raw(" "),
+                        Span::raw(number(event.strategy.count)),
+                    ]),
+                    event.block_name.as_str(),
+                )
+            };
+            if self.is_watched(&event.match_id) {
+                format.push_span(Span::styled(", watched", info_style));
+            }
+            Text::from(format)
+                .left_aligned()
+                .style(style)
+                .render(event_low_area, buf);
 
-            Text::from(format!(
-                "{} - {}",
-                event.block_name.to_owned(),
-                event.league_name,
-            ))
+            Text::from(Line::from(vec![
+                Span::raw(block),
+                Span::raw(" - "),
+                Span::raw(event.league_name.as_str()),
+            ]))
             .right_aligned()
-            .style(style)
+            .style(info_style)
             .render(event_low_area, buf);
 
             current_height += 1;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::models::{MatchResult, Strategy, Team};
+
+    fn event(match_id: &str, league: &str) -> Event {
+        Event {
+            match_id: match_id.to_string(),
+            start_time: Local.with_ymd_and_hms(2026, 10, 14, 12, 0, 0).unwrap(),
+            league_name: league.to_string(),
+            league_slug: league.to_string(),
+            block_name: "Finals".to_string(),
+            strategy: Strategy {
+                strat_type: StratType::BestOf("Best of".to_string()),
+                count: 5,
+            },
+            state: MatchState::Unstarted("Unstarted".to_string()),
+            result: None,
+            teams: vec![Team::tbd(), Team::tbd()],
+        }
+    }
+
+    fn schedule() -> Events {
+        let config = Config {
+            data_dir: std::env::temp_dir().join(format!("lol-cal-events-{}", std::process::id())),
+            ..Config::default()
+        };
+        let mut events = Events::new(Arc::new(config));
+        // The league fetched last owns the shared match
+        events.add_events("b".to_string(), vec![event("1", "b")]);
+        events.add_events("a".to_string(), vec![event("1", "a"), event("2", "a")]);
+        events
+    }
+
+    fn active_ids(events: &Events) -> Vec<&str> {
+        events
+            .active_events()
+            .map(|e| e.match_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn keeps_a_shared_match_another_league_lists() {
+        let mut events = schedule();
+        events.set_active("b".to_string());
+        events.add_events("a".to_string(), vec![event("2", "a")]);
+        assert_eq!(active_ids(&events), ["1"]);
+        assert_eq!(events.league_events("b").len(), 1);
+    }
+
+    #[test]
+    fn shows_a_shared_match_while_a_listing_league_is_active() {
+        let mut events = schedule();
+        events.set_active("a".to_string());
+        events.set_active("b".to_string());
+        events.unset_active("a");
+        assert_eq!(active_ids(&events), ["1"]);
+        events.set_active("a".to_string());
+        events.unset_active("b");
+        assert_eq!(active_ids(&events), ["1", "2"]);
+    }
+
+    #[test]
+    fn removes_a_match_no_league_lists() {
+        let mut events = schedule();
+        events.set_active("a".to_string());
+        events.add_events("a".to_string(), vec![event("1", "a")]);
+        assert_eq!(active_ids(&events), ["1"]);
+        events.add_events("b".to_string(), Vec::new());
+        events.add_events("a".to_string(), Vec::new());
+        assert!(events.events.is_empty());
+    }
+
+    #[test]
+    fn leaves_the_match_itself_out_of_the_head_to_head() {
+        let completed = |match_id: &str, game_wins| Event {
+            state: MatchState::Completed("Completed".to_string()),
+            result: Some(MatchResult { game_wins }),
+            teams: vec![Team::tbd(), Team::tbd()],
+            ..event(match_id, "a")
+        };
+        let mut events = schedule();
+        events.add_events(
+            "a".to_string(),
+            vec![completed("1", (3, 1)), completed("2", (3, 2))],
+        );
+        let viewed = completed("2", (3, 2));
+        assert_eq!(events.head_to_head(&viewed), (1, 0, 1));
+    }
+}
@@ -22,9 +22,7 @@ impl FillChar {
 
 impl Widget for FillChar {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let fill_line: String = std::iter::repeat(self.ch)
-            .take(area.width as usize)
-            .collect();
+        let fill_line: String = std::iter::repeat_n(self.ch, area.width as usize).collect();
 
         for y in area.y..area.y + area.height {
             buf.set_string(area.x, y, &fill_line, self.style);
@@ -0,0 +1,106 @@
+use std::{cell::Cell, sync::Arc};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Color,
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget, WidgetRef},
+};
+
+use crate::{config::Config, resources::Failure};
+
+const HEALTH_WIDTH: u16 = 72;
+const HEALTH_HEIGHT: u16 = 20;
+
+// The failed requests of this session, newest first
+#[derive(Debug)]
+pub struct Health {
+    failures: Vec<Failure>,
+    scroll: u16,
+    // Set when rendering, so scrolling stops once the last line is at the bottom
+    max_scroll: Cell<u16>,
+    config: Arc<Config>,
+}
+
+impl Health {
+    pub fn new(mut failures: Vec<Failure>, config: Arc<Config>) -> Self {
+        failures.reverse();
+        Self {
+            failures,
+            scroll: 0,
+            max_scroll: Cell::new(0),
+            config,
+        }
+    }
+
+    pub fn up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub fn down(&mut self) {
+        self.scroll = (self.scroll + 1).min(self.max_scroll.get());
+    }
+
+    // Two lines a failure, Ex: "14:02:11  getSchedule 98767991310872058  503, recovered 14:04:30"
+    // Messages are wrapped to `width`, as errors tend to include the whole url
+    fn lines(&self, width: u16) -> Vec<Line<'_>> {
+        let styles = &self.config.style;
+        if self.failures.is_empty() {
+            return vec![Line::from("No failed requests this session").style(styles.default)];
+        }
+        let mut lines = Vec::new();
+        for failure in &self.failures {
+            let mut header = format!("{}  {}", failure.time.format("%H:%M:%S"), failure.endpoint);
+            if let Some(status) = failure.status {
+                header.push_str(&format!("  {}", status));
+            }
+            match failure.recovered {
+                Some(time) => header.push_str(&format!(", recovered {}", time.format("%H:%M:%S"))),
+                None => header.push_str(", not recovered"),
+            }
+            let header_style = match failure.recovered {
+                Some(_) => styles.date(),
+                None => styles.selected,
+            };
+            lines.push(Line::from(header).style(header_style));
+            let chars: Vec<char> = failure.message.chars().collect();
+            for chunk in chars.chunks((width as usize).saturating_sub(2).max(1)) {
+                let text: String = chunk.iter().collect();
+                lines.push(Line::from(format!("  {}", text)).style(styles.default));
+            }
+        }
+        lines
+    }
+}
+
+impl WidgetRef for &Health {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let styles = &self.config.style;
+        let width = HEALTH_WIDTH.min(area.width);
+        let height = HEALTH_HEIGHT.min(area.height);
+        let popup_area = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        let title = format!("Network health ({} failed)", self.failures.len());
+        let block = Block::new()
+            .borders(Borders::all())
+            .border_type(styles.popup_border())
+            .border_style(styles.highlight.bg(Color::Reset))
+            .title(Line::from(title).centered().style(styles.title()));
+        let inner_area = block.inner(popup_area);
+        Clear.render(popup_area, buf);
+        block.render(popup_area, buf);
+
+        let lines = self.lines(inner_area.width);
+        let max_scroll = (lines.len() as u16).saturating_sub(inner_area.height);
+        self.max_scroll.set(max_scroll);
+        Paragraph::new(lines)
+            .scroll((self.scroll.min(max_scroll), 0))
+            .render(inner_area, buf);
+    }
+}
@@ -1,17 +1,23 @@
-use std::rc::Rc;
+use std::{collections::HashSet, path::PathBuf, sync::Arc};
 
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
+    layout::{Margin, Rect},
     style::{Color, Style},
     text::Line,
     widgets::{
         Block, Borders, List, ListItem, ListState, StatefulWidget, StatefulWidgetRef, WidgetRef,
     },
 };
-use serde::{Deserialize, Serialize};
 
-use crate::config::{Config, Styles};
+use tracing::error;
+
+use crate::config::{Config, LeagueSort, Styles, utils::get_border_connections};
+use crate::models::{Event, League};
+use crate::resources;
+use chrono::{DateTime, Local};
+
+const RECENT_FILE: &str = "recent_leagues.json";
 
 #[derive(Debug, Default)]
 pub struct LeaguesState {
@@ -19,62 +25,123 @@ pub struct LeaguesState {
     pub list_state: ListState,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct League {
-    pub name: String,
-    pub region: String,
-    pub id: String,
-    pub selected: bool,
-}
-
 impl League {
-    fn to_list_item(&self, styles: &Styles) -> ListItem {
-        ListItem::new(format!("{}", self.name)).style(match self.selected {
+    fn to_list_item(&self, styles: &Styles) -> ListItem<'_> {
+        ListItem::new(self.name.to_string()).style(match self.selected {
             true => styles.selected,
             false => styles.default,
         })
     }
 }
 
+// A line of the leagues pane, the cursor skips headers
+enum Row<'a> {
+    Header(&'a str),
+    League(&'a League),
+}
+
 #[derive(Debug)]
 pub struct Leagues {
     pub longest: u16,
     pub leagues: Vec<League>,
-    config: Rc<Config>,
+    pub sort: LeagueSort,
+    hidden_regions: Vec<String>,
+    // Only show leagues with a match yet to start
+    pub upcoming_only: bool,
+    // Ids of leagues with a match yet to start, `None` until the cache has been read
+    upcoming: Option<HashSet<String>>,
+    // Ids of recently activated leagues, newest first
+    recent: Vec<String>,
+    recent_path: PathBuf,
+    config: Arc<Config>,
 }
 
 impl Leagues {
-    pub fn new(config: Rc<Config>) -> Self {
+    pub fn new(config: Arc<Config>) -> Self {
+        let recent_path = config.data_dir.join(RECENT_FILE);
+        let recent = resources::load_json(&recent_path);
         Self {
             longest: 0,
             leagues: Vec::new(),
-            config: config,
+            sort: config.league_sort,
+            hidden_regions: config.hidden_regions.clone(),
+            upcoming_only: config.upcoming_leagues_only,
+            upcoming: None,
+            recent,
+            recent_path,
+            config,
         }
     }
 
     pub fn select(&mut self, state: &ListState) -> Option<(bool, String)> {
-        if let Some(i) = state.selected() {
-            if let Some(league) = self.leagues.get_mut(i) {
-                league.selected = !league.selected;
-                if league.selected {
-                    return Some((true, league.id.clone()));
-                } else {
-                    return Some((false, league.id.clone()));
-                }
+        let id = self.league_at(state.selected()?)?.id.clone();
+        if let Some(league) = self.leagues.iter_mut().find(|l| l.id == id) {
+            league.selected = !league.selected;
+            if league.selected {
+                return Some((true, league.id.clone()));
+            } else {
+                return Some((false, league.id.clone()));
             }
         }
-        return None;
+        None
     }
 
     pub fn select_name(&mut self, to_select: &str) -> Option<String> {
-        if let Some(league) = self.leagues.iter_mut().find(|l| l.name == to_select) {
-            league.selected = true;
+        self.set_selected_name(to_select, true)
+    }
+
+    pub fn deselect_name(&mut self, to_deselect: &str) -> Option<String> {
+        self.set_selected_name(to_deselect, false)
+    }
+
+    pub fn is_name_selected(&self, name: &str) -> Option<bool> {
+        self.leagues
+            .iter()
+            .find(|l| l.matches_name(name))
+            .map(|l| l.selected)
+    }
+
+    fn set_selected_name(&mut self, name: &str, selected: bool) -> Option<String> {
+        if let Some(league) = self.leagues.iter_mut().find(|l| l.matches_name(name)) {
+            league.selected = selected;
             Some(league.id.to_string())
         } else {
             None
         }
     }
 
+    // Selects exactly the leagues in `ids`, returning the ids that changed and their new state
+    pub fn set_selected_ids(&mut self, ids: &[String]) -> Vec<(bool, String)> {
+        let mut changed = Vec::new();
+        for league in self.leagues.iter_mut() {
+            let selected = ids.contains(&league.id);
+            if league.selected != selected {
+                league.selected = selected;
+                changed.push((selected, league.id.to_string()));
+            }
+        }
+        changed
+    }
+
+    // Slugs are stable across machines, unlike the position in the list
+    pub fn get_selected_slugs(&self) -> Vec<String> {
+        self.leagues
+            .iter()
+            .filter(|l| l.selected)
+            .map(|l| match l.slug.is_empty() {
+                true => l.id.to_string(),
+                false => l.slug.to_string(),
+            })
+            .collect()
+    }
+
+    pub fn find_id(&self, name: &str) -> Option<String> {
+        self.leagues
+            .iter()
+            .find(|l| l.matches_name(name))
+            .map(|l| l.id.to_string())
+    }
+
     pub fn get_selected_ids(&self) -> Vec<String> {
         self.leagues
             .iter()
@@ -85,15 +152,225 @@ impl Leagues {
 
     pub fn set_leagues(&mut self, leagues: Vec<League>) {
         self.leagues = leagues;
-        self.longest = self
+        self.set_sort(self.sort);
+        self.update_longest();
+    }
+
+    // Sorting is stable, so leagues with the same priority keep the API order
+    pub fn set_sort(&mut self, sort: LeagueSort) {
+        self.sort = sort;
+        match sort {
+            LeagueSort::Priority => self.leagues.sort_by_key(|l| l.priority),
+            LeagueSort::Name => self.leagues.sort_by_key(|l| l.name.to_lowercase()),
+            LeagueSort::Region => self
+                .leagues
+                .sort_by_key(|l| (l.region.to_lowercase(), l.priority)),
+        }
+    }
+
+    // Row of a league in the pane, in the recent section if it is listed there
+    pub fn position(&self, id: &str) -> Option<usize> {
+        self.rows()
+            .iter()
+            .position(|r| matches!(r, Row::League(l) if l.id == id))
+    }
+
+    // Row of the first league of all leagues whose name starts with `prefix`, ignoring case
+    pub fn find_prefix(&self, prefix: &str) -> Option<usize> {
+        let prefix = prefix.to_lowercase();
+        let start = self.recent_rows();
+        self.rows()
+            .iter()
+            .enumerate()
+            .skip(start)
+            .find(
+                |(_, r)| matches!(r, Row::League(l) if l.name.to_lowercase().starts_with(&prefix)),
+            )
+            .map(|(i, _)| i)
+    }
+
+    // The league on a row of the pane, `None` for headers
+    pub fn league_at(&self, row: usize) -> Option<&League> {
+        match self.rows().get(row) {
+            Some(Row::League(league)) => Some(league),
+            _ => None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.visible().next().is_none()
+    }
+
+    // Lines of the pane, visible recent leagues are listed above all visible leagues
+    fn rows(&self) -> Vec<Row<'_>> {
+        let recent: Vec<&League> = self
+            .recent
+            .iter()
+            .filter_map(|id| self.visible().find(|l| &l.id == id))
+            .take(self.config.recent_leagues)
+            .collect();
+        let mut rows = Vec::new();
+        if !recent.is_empty() {
+            rows.push(Row::Header(&self.config.strings.recent));
+            rows.extend(recent.into_iter().map(Row::League));
+            rows.push(Row::Header(&self.config.strings.leagues));
+        }
+        rows.extend(self.visible().map(Row::League));
+        rows
+    }
+
+    // Rows before the first of all leagues
+    fn recent_rows(&self) -> usize {
+        self.rows()
+            .iter()
+            .rposition(|r| matches!(r, Row::Header(_)))
+            .map_or(0, |i| i + 1)
+    }
+
+    pub fn select_first(&self, state: &mut ListState) {
+        state.select(self.next_league(0, 1));
+    }
+
+    pub fn select_previous(&self, state: &mut ListState) {
+        let row = state.selected().unwrap_or_default();
+        if let Some(row) = row.checked_sub(1).and_then(|r| self.next_league(r, -1)) {
+            state.select(Some(row));
+        }
+    }
+
+    pub fn select_next(&self, state: &mut ListState) {
+        let row = state.selected().map_or(0, |r| r + 1);
+        if let Some(row) = self.next_league(row, 1) {
+            state.select(Some(row));
+        }
+    }
+
+    // Moves the cursor onto a league after rows were removed
+    pub fn clamp(&self, state: &mut ListState) {
+        let Some(row) = state.selected() else {
+            return;
+        };
+        let last = self.rows().len().checked_sub(1);
+        let row = last.map(|last| row.min(last));
+        state.select(row.and_then(|r| self.next_league(r, 1).or(self.next_league(r, -1))));
+    }
+
+    // The first league row from `row` on in the direction of `step`
+    fn next_league(&self, row: usize, step: isize) -> Option<usize> {
+        let rows = self.rows();
+        let mut row = row as isize;
+        while let Some(r) = rows.get(usize::try_from(row).ok()?) {
+            if let Row::League(_) = r {
+                return Some(row as usize);
+            }
+            row += step;
+        }
+        None
+    }
+
+    // Moves the league to the top of the recent section, the cursor stays on its league
+    pub fn add_recent(&mut self, id: &str, state: &mut ListState) {
+        const MAX_RECENT: usize = 20;
+        if self.config.recent_leagues == 0 {
+            return;
+        }
+        let rows = self.rows().len();
+        let cursor = state.selected();
+        let in_recent = cursor.is_some_and(|c| c < self.recent_rows());
+
+        self.recent.retain(|r| r != id);
+        self.recent.insert(0, id.to_string());
+        self.recent.truncate(MAX_RECENT);
+        self.update_longest();
+
+        // The recent section only grows above all leagues
+        let row = match (cursor, in_recent) {
+            (Some(_), true) => self.position(id),
+            (Some(c), false) => (self.rows().len() + c).checked_sub(rows),
+            (None, _) => None,
+        };
+        state.select(row);
+        if let Err(e) = self.save_recent() {
+            error!("Failed to save '{}': {}", self.recent_path.display(), e);
+        }
+    }
+
+    fn save_recent(&self) -> std::io::Result<()> {
+        resources::save_json(&self.recent_path, &self.recent)
+    }
+
+    // Leagues shown in the pane, leagues in hidden regions keep their selection. Selected
+    // leagues without upcoming matches stay, so they can still be deselected
+    pub fn visible(&self) -> impl Iterator<Item = &League> {
+        self.leagues.iter().filter(|l| {
+            !is_region_hidden(&self.hidden_regions, &l.region)
+                && (!self.upcoming_only
+                    || l.selected
+                    || self.upcoming.as_ref().is_none_or(|u| u.contains(&l.id)))
+        })
+    }
+
+    pub fn set_upcoming(&mut self, upcoming: HashSet<String>) {
+        self.upcoming = Some(upcoming);
+        self.update_longest();
+    }
+
+    // Keeps the upcoming leagues current with a freshly fetched schedule
+    pub fn update_upcoming(&mut self, id: &str, events: &[Event], now: DateTime<Local>) {
+        let Some(upcoming) = self.upcoming.as_mut() else {
+            return;
+        };
+        match events.iter().any(|e| e.is_upcoming(now)) {
+            true => upcoming.insert(id.to_string()),
+            false => upcoming.remove(id),
+        };
+        self.update_longest();
+    }
+
+    pub fn set_upcoming_only(&mut self, upcoming_only: bool) {
+        self.upcoming_only = upcoming_only;
+        self.update_longest();
+    }
+
+    pub fn is_region_hidden(&self, region: &str) -> bool {
+        is_region_hidden(&self.hidden_regions, region)
+    }
+
+    // Returns the region as named by the leagues, if any league has it
+    pub fn set_region_hidden(&mut self, region: &str, hidden: bool) -> Option<String> {
+        let region = self
             .leagues
             .iter()
-            .max_by_key(|l| l.name.len())
-            .map(|item| item.name.len())
+            .find(|l| l.region.eq_ignore_ascii_case(region))
+            .map(|l| l.region.to_string())?;
+        self.hidden_regions
+            .retain(|r| !r.eq_ignore_ascii_case(&region));
+        if hidden {
+            self.hidden_regions.push(region.to_string());
+        }
+        self.update_longest();
+        Some(region)
+    }
+
+    fn update_longest(&mut self) {
+        self.longest = self
+            .rows()
+            .iter()
+            .map(|r| match r {
+                Row::Header(header) => header.chars().count(),
+                Row::League(league) => league.name.len(),
+            })
+            .max()
             .unwrap_or_default() as u16;
     }
 }
 
+fn is_region_hidden(hidden_regions: &[String], region: &str) -> bool {
+    hidden_regions
+        .iter()
+        .any(|r| r.eq_ignore_ascii_case(region))
+}
+
 impl StatefulWidgetRef for &Leagues {
     type State = LeaguesState;
 
@@ -101,7 +378,8 @@ impl StatefulWidgetRef for &Leagues {
         let styles = &self.config.style;
 
         let inner_area = {
-            if let (Some(block), Some(set)) = (styles.border, styles.border_set) {
+            let border = styles.leagues_border();
+            if let (Some(block), Some(set)) = (border, get_border_connections(border)) {
                 let border_style = if state.focused {
                     styles.highlight.bg(Color::Reset)
                 } else {
@@ -122,7 +400,7 @@ impl StatefulWidgetRef for &Leagues {
                         x: area.left(),
                         y: area.top() + 2,
                         width: area.width,
-                        height: 1 as u16,
+                        height: 1,
                     };
 
                     let date_header = Line::from(format!(
@@ -138,12 +416,12 @@ impl StatefulWidgetRef for &Leagues {
                         x: area.left() + 1,
                         y: area.top() + 1,
                         width: area.width.saturating_sub(2),
-                        height: 1 as u16,
+                        height: 1,
                     };
 
-                    let title = Line::from("Leagues")
+                    let title = Line::from(self.config.strings.leagues.as_str())
                         .centered()
-                        .style(styles.highlight.bg(Color::Reset));
+                        .style(styles.title());
                     title.render_ref(title_area, buf);
 
                     inner.y += 2;
@@ -158,19 +436,28 @@ impl StatefulWidgetRef for &Leagues {
             }
         };
 
-        let mut highlight_style = Style::default();
-        highlight_style.bg = styles.highlight.bg;
+        let highlight_style = Style {
+            bg: styles.highlight.bg,
+            ..Default::default()
+        };
 
         let items: Vec<ListItem> = self
-            .leagues
-            .iter()
-            .map(|l| l.to_list_item(styles))
+            .rows()
+            .into_iter()
+            .map(|r| match r {
+                Row::Header(header) => ListItem::new(header).style(styles.date()),
+                Row::League(league) => league.to_list_item(styles),
+            })
             .collect();
 
         let list = List::new(items)
             .highlight_symbol("* ")
             .highlight_style(highlight_style);
 
-        list.render(inner_area, buf, &mut state.list_state);
+        list.render(
+            inner_area.inner(Margin::new(self.config.padding, 0)),
+            buf,
+            &mut state.list_state,
+        );
     }
 }
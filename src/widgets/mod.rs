@@ -1,3 +1,9 @@
+pub mod command_line;
+pub mod details;
 pub mod events;
 pub mod fillchar;
+pub mod health;
 pub mod leagues;
+pub mod palette;
+pub mod queue;
+pub mod toast;
@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{
+        Block, Borders, Clear, List, ListItem, ListState, StatefulWidget, Widget, WidgetRef,
+    },
+};
+
+use crate::{config::Config, config::keys::COMMANDS, event::AppEvent};
+
+const PALETTE_WIDTH: u16 = 40;
+const PALETTE_HEIGHT: u16 = 16;
+
+#[derive(Debug)]
+pub struct Palette {
+    pub query: String,
+    list_state: ListState,
+    matches: Vec<(&'static str, AppEvent)>,
+    config: Arc<Config>,
+}
+
+impl Palette {
+    pub fn new(config: Arc<Config>) -> Self {
+        let mut palette = Self {
+            query: String::new(),
+            list_state: ListState::default(),
+            matches: Vec::new(),
+            config,
+        };
+        palette.update_matches();
+        palette
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.update_matches();
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.update_matches();
+    }
+
+    pub fn up(&mut self) {
+        self.list_state.select_previous();
+    }
+
+    pub fn down(&mut self) {
+        if self
+            .list_state
+            .selected()
+            .is_some_and(|i| i + 1 < self.matches.len())
+        {
+            self.list_state.select_next();
+        }
+    }
+
+    pub fn selected(&self) -> Option<AppEvent> {
+        self.list_state
+            .selected()
+            .and_then(|i| self.matches.get(i))
+            .map(|(_, event)| event.clone())
+    }
+
+    fn update_matches(&mut self) {
+        let mut scored: Vec<(i32, &'static str, AppEvent)> = COMMANDS
+            .iter()
+            .filter_map(|(name, event)| {
+                fuzzy_score(&self.query, name).map(|score| (score, *name, event.clone()))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+        self.matches = scored
+            .into_iter()
+            .map(|(_, name, event)| (name, event))
+            .collect();
+        self.list_state.select(if self.matches.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+}
+
+// Scores `candidate` if every character of `query` appears in it in order.
+// Consecutive characters and characters starting a word score higher.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let mut score = 0;
+    let mut last_match: Option<usize> = None;
+    let mut chars = candidate.char_indices();
+
+    for q in query.chars().filter(|c| !c.is_whitespace()) {
+        let (i, c) = chars.find(|(_, c)| c.eq_ignore_ascii_case(&q))?;
+        score += 1;
+        if last_match.is_some_and(|last| last + 1 == i) {
+            score += 5;
+        }
+        if i == 0 || c.is_uppercase() {
+            score += 3;
+        }
+        last_match = Some(i);
+    }
+    Some(score)
+}
+
+impl WidgetRef for &Palette {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let styles = &self.config.style;
+        let width = PALETTE_WIDTH.min(area.width);
+        let height = PALETTE_HEIGHT.min(area.height);
+        let popup_area = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        let block = Block::new()
+            .borders(Borders::all())
+            .border_type(styles.popup_border())
+            .border_style(styles.highlight.bg(Color::Reset))
+            .title(Line::from("Commands").centered().style(styles.title()));
+        let inner_area = block.inner(popup_area);
+        Clear.render(popup_area, buf);
+        block.render(popup_area, buf);
+
+        let [query_area, list_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(inner_area);
+
+        Line::from(vec![
+            Span::from("> ").style(styles.highlight),
+            Span::from(self.query.as_str()).style(styles.default),
+        ])
+        .render(query_area, buf);
+
+        let items: Vec<ListItem> = self
+            .matches
+            .iter()
+            .map(|(name, _)| ListItem::new(*name).style(styles.default))
+            .collect();
+        let highlight_style = Style {
+            bg: styles.highlight.bg,
+            ..styles.selected
+        };
+        let list = List::new(items)
+            .highlight_symbol("* ")
+            .highlight_style(highlight_style);
+        let mut list_state = self.list_state.clone();
+        StatefulWidget::render(list, list_area, buf, &mut list_state);
+    }
+}
@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{
+        Block, Borders, Clear, List, ListItem, ListState, Paragraph, StatefulWidget, Widget,
+        WidgetRef,
+    },
+};
+
+use crate::{config::Config, models::Event};
+
+const QUEUE_WIDTH: u16 = 72;
+const QUEUE_HEIGHT: u16 = 20;
+
+// Completed matches that are not marked as watched, never showing results
+#[derive(Debug)]
+pub struct Queue {
+    events: Vec<Event>,
+    list_state: ListState,
+    // Only matches of favorite teams
+    pub favorites_only: bool,
+    config: Arc<Config>,
+}
+
+impl Queue {
+    pub fn new(favorites_only: bool, config: Arc<Config>) -> Self {
+        Self {
+            events: Vec::new(),
+            list_state: ListState::default(),
+            favorites_only,
+            config,
+        }
+    }
+
+    // Keeps the cursor on the same row, or the last one if the list got shorter
+    pub fn set_events(&mut self, events: Vec<Event>) {
+        self.events = events;
+        let selected = self.list_state.selected().unwrap_or_default();
+        self.list_state.select(match self.events.len() {
+            0 => None,
+            len => Some(selected.min(len - 1)),
+        });
+    }
+
+    pub fn up(&mut self) {
+        self.list_state.select_previous();
+    }
+
+    pub fn down(&mut self) {
+        if self
+            .list_state
+            .selected()
+            .is_some_and(|i| i + 1 < self.events.len())
+        {
+            self.list_state.select_next();
+        }
+    }
+
+    pub fn selected(&self) -> Option<&Event> {
+        self.list_state.selected().and_then(|i| self.events.get(i))
+    }
+}
+
+impl WidgetRef for &Queue {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let styles = &self.config.style;
+        let width = QUEUE_WIDTH.min(area.width);
+        let height = QUEUE_HEIGHT.min(area.height);
+        let popup_area = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        let title = match self.favorites_only {
+            true => format!("VOD queue, favorites ({})", self.events.len()),
+            false => format!("VOD queue ({})", self.events.len()),
+        };
+        let block = Block::new()
+            .borders(Borders::all())
+            .border_type(styles.popup_border())
+            .border_style(styles.highlight.bg(Color::Reset))
+            .title(Line::from(title).centered().style(styles.title()));
+        let inner_area = block.inner(popup_area);
+        Clear.render(popup_area, buf);
+        block.render(popup_area, buf);
+
+        if self.events.is_empty() {
+            Paragraph::new("No unwatched matches")
+                .style(styles.default)
+                .render(inner_area, buf);
+            return;
+        }
+
+        // Ex: "Tue 13 Oct 18:00  T1 vs GEN   LCK Week 1"
+        let vs = &self.config.strings.vs;
+        let items: Vec<ListItem> = self
+            .events
+            .iter()
+            .map(|e| {
+                let teams = format!("{} {} {}", e.teams[0].short, vs, e.teams[1].short);
+                let style = match e.teams.iter().any(|t| self.config.is_favorite(&t.short)) {
+                    true => styles.favorite.unwrap_or(styles.default),
+                    false => styles.default,
+                };
+                ListItem::new(format!(
+                    "{}  {:<14}{} {}",
+                    e.start_time.format("%a %d %b %H:%M"),
+                    teams,
+                    e.league_name,
+                    e.block_name
+                ))
+                .style(style)
+            })
+            .collect();
+        let highlight_style = Style {
+            bg: styles.highlight.bg,
+            ..styles.selected
+        };
+        let list = List::new(items)
+            .highlight_symbol("* ")
+            .highlight_style(highlight_style);
+        let mut list_state = self.list_state.clone();
+        StatefulWidget::render(list, inner_area, buf, &mut list_state);
+    }
+}
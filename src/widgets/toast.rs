@@ -0,0 +1,107 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Color,
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget, WidgetRef, Wrap},
+};
+
+use crate::config::Config;
+
+pub const TOAST_DURATION: Duration = Duration::from_secs(5);
+const TOAST_WIDTH: u16 = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub level: ToastLevel,
+    pub message: String,
+    created: Instant,
+}
+
+#[derive(Debug)]
+pub struct Toasts {
+    toasts: Vec<Toast>,
+    config: Arc<Config>,
+}
+
+impl Toasts {
+    pub fn new(config: Arc<Config>) -> Self {
+        Self {
+            toasts: Vec::new(),
+            config,
+        }
+    }
+
+    pub fn push(&mut self, level: ToastLevel, message: String) {
+        self.toasts.push(Toast {
+            level,
+            message,
+            created: Instant::now(),
+        });
+    }
+
+    // Returns whether any toast expired
+    pub fn expire(&mut self) -> bool {
+        let count = self.toasts.len();
+        self.toasts.retain(|t| t.created.elapsed() < TOAST_DURATION);
+        self.toasts.len() != count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+}
+
+impl WidgetRef for &Toasts {
+    // Toasts are stacked upwards from the bottom right corner of the area
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let styles = &self.config.style;
+        let width = TOAST_WIDTH.min(area.width);
+        let mut bottom = area.bottom();
+
+        for toast in self.toasts.iter().rev() {
+            let (title, style) = match toast.level {
+                ToastLevel::Info => ("Info", styles.highlight.bg(Color::Reset)),
+                ToastLevel::Warning => ("Warning", styles.highlight.bg(Color::Reset)),
+                ToastLevel::Error => ("Error", styles.selected.bg(Color::Reset)),
+            };
+            let text_width = width.saturating_sub(2).max(1) as usize;
+            let height = (toast.message.chars().count().div_ceil(text_width) as u16).max(1) + 2;
+            if bottom < area.top() + height {
+                break;
+            }
+
+            let toast_area = Rect {
+                x: area.right().saturating_sub(width),
+                y: bottom - height,
+                width,
+                height,
+            };
+            bottom -= height;
+
+            let block = Block::new()
+                .borders(Borders::all())
+                .border_type(styles.popup_border())
+                .border_style(style)
+                .title(Line::from(title).style(style));
+            Clear.render(toast_area, buf);
+            Paragraph::new(toast.message.as_str())
+                .style(styles.default)
+                .wrap(Wrap { trim: true })
+                .block(block)
+                .render(toast_area, buf);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+// Renders the TUI from replayed fixtures at a frozen time and compares the frame with the
+// snapshot in `tests/snapshots`. Run with `UPDATE_SNAPSHOTS=1` to write the snapshot anew
+#![cfg(feature = "tui")]
+
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, TimeZone};
+use lol_cal::{app::App, clock::Clock, config::Config, net::Source};
+use ratatui::buffer::Buffer;
+
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+}
+
+// A data dir of its own, so files of the user like watched.json don't change the frame
+fn data_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("lol-cal-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn to_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            text.push_str(buffer[(x, y)].symbol());
+        }
+        text.push('\n');
+    }
+    text
+}
+
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{}.txt", name));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Failed to read '{}': {}", path.display(), e));
+    assert_eq!(actual, expected, "frame differs from '{}'", path.display());
+}
+
+#[tokio::test]
+async fn schedule_snapshot() {
+    // SAFETY: the only test of this binary, nothing else reads the environment at the same time
+    unsafe { std::env::set_var("TZ", "UTC") };
+    let data_dir = data_dir("render");
+    let config = Config {
+        data_dir: data_dir.clone(),
+        source: Source::Replay(fixtures()),
+        default_leagues: vec!["LCK".to_string()],
+        ..Config::default()
+    };
+    let now = Local.with_ymd_and_hms(2026, 10, 14, 11, 0, 0).unwrap();
+    let mut app = App::new(config).with_clock(Clock::frozen(now));
+    app.load().await;
+    let buffer = app.render(100, 20).unwrap();
+    let _ = std::fs::remove_dir_all(&data_dir);
+
+    assert_snapshot("schedule", &to_text(&buffer));
+}